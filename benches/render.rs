@@ -0,0 +1,77 @@
+//! Rysowanie przykładowej talii bez animacji. Oprócz czasu wypisuje średnią
+//! liczbę alokacji na narysowany wiersz ramki.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use rustlab_presentations::Config;
+use rustlab_presentations::bench::{Deck, demo_script};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn demo_deck() -> Deck {
+    let path: PathBuf =
+        std::env::temp_dir().join(format!("presentation-bench-{}.txt", std::process::id()));
+    std::fs::write(&path, demo_script(200, 7)).unwrap();
+    let deck = Deck::load(std::slice::from_ref(&path)).unwrap();
+    std::fs::remove_file(path).unwrap();
+    deck
+}
+
+fn render(criterion: &mut Criterion) {
+    let deck = demo_deck();
+    let config = Config::builder()
+        .animations(false)
+        .frame_width(100)
+        .build()
+        .unwrap();
+    let mut out = Vec::with_capacity(1 << 20);
+
+    deck.render(&mut out, &config).unwrap();
+    let rows = out.iter().filter(|byte| **byte == b'\n').count();
+    out.clear();
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    deck.render(&mut out, &config).unwrap();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    eprintln!(
+        "{} sekwencji, {} wierszy, {:.2} alokacji na wiersz",
+        deck.slides(),
+        rows,
+        allocations as f64 / rows as f64
+    );
+
+    criterion.bench_function("render_demo_deck", |bencher| {
+        bencher.iter(|| {
+            out.clear();
+            deck.render(&mut out, black_box(&config)).unwrap();
+            black_box(out.len())
+        })
+    });
+}
+
+criterion_group!(benches, render);
+criterion_main!(benches);
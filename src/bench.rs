@@ -0,0 +1,47 @@
+//! Wejście benchmarków z `benches/`: talia wczytana jak z wiersza poleceń
+//! i narysowana bez animacji, tak jak robi to pętla prezentacji.
+
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::interaction::Slide;
+use crate::rules::RuleSet;
+use crate::{Config, JoinMode, animate_line, demo, load_deck, print_frame_bottom, print_frame_top};
+
+pub struct Deck {
+    slides: Vec<Slide>,
+}
+
+impl Deck {
+    pub fn load(paths: &[PathBuf]) -> io::Result<Self> {
+        let slides = load_deck(
+            paths,
+            JoinMode::Contiguous,
+            false,
+            false,
+            &RuleSet::default(),
+        )?;
+        Ok(Self { slides })
+    }
+
+    pub fn slides(&self) -> usize {
+        self.slides.len()
+    }
+
+    /// Każda sekwencja w ramce: krawędzie i wszystkie segmenty.
+    pub fn render(&self, out: &mut dyn Write, config: &Config) -> io::Result<()> {
+        for (index, slide) in self.slides.iter().enumerate() {
+            print_frame_top(out, config, slide.frame_title(), None)?;
+            for segment in slide.segments() {
+                animate_line(out, config, index, segment, false)?;
+            }
+            print_frame_bottom(out, config, None)?;
+        }
+        Ok(())
+    }
+}
+
+/// Skrypt przykładowej prezentacji jak z `--generate-demo`.
+pub fn demo_script(count: usize, seed: u64) -> String {
+    demo::generate(count, seed)
+}
@@ -0,0 +1,43 @@
+use std::borrow::Cow;
+
+use chrono::Local;
+use chrono::format::{Item, StrftimeItems};
+
+pub(crate) const DATE_TOKEN: &str = "@date";
+pub(crate) const TIME_TOKEN: &str = "@time";
+pub(crate) const CLOCK_TOKEN: &str = "@clock";
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+pub(crate) fn validate_format(format: &str) -> Result<(), String> {
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("Nieprawidłowy format czasu: {}", format));
+    }
+    Ok(())
+}
+
+pub(crate) fn now(format: &str) -> String {
+    Local::now().format(format).to_string()
+}
+
+pub(crate) fn mentions_clock(text: &str) -> bool {
+    text.contains(CLOCK_TOKEN)
+}
+
+pub(crate) fn expand_tokens<'a>(text: &'a str, time_format: &str) -> Cow<'a, str> {
+    if !text.contains('@') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut expanded = text.to_string();
+    if expanded.contains(DATE_TOKEN) {
+        expanded = expanded.replace(DATE_TOKEN, &now(DATE_FORMAT));
+    }
+    if expanded.contains(TIME_TOKEN) || expanded.contains(CLOCK_TOKEN) {
+        let time = now(time_format);
+        expanded = expanded
+            .replace(TIME_TOKEN, &time)
+            .replace(CLOCK_TOKEN, &time);
+    }
+    Cow::Owned(expanded)
+}
@@ -0,0 +1,480 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::theme::ThemePalette;
+use crate::{
+    Background, Config, DEFAULT_FRAME_WIDTH, Easing, EnterAction, FooterField, PositionFallback,
+    ThemeName, TypingCursor, clock, highlight_pattern, load_banner, replay,
+};
+
+const MIN_CONTRAST_BOOST: f32 = 1.0;
+const MAX_CONTRAST_BOOST: f32 = 3.0;
+
+/// Budowanie konfiguracji renderowania bez udziału CLI i zmiennych
+/// środowiskowych – jedyny wspierany sposób tworzenia `Config` w obrębie
+/// procesu (np. przy osadzaniu). Domyślne ustawienia odpowiadają uruchomieniu
+/// bez opcji, z wyjątkiem szerokości ramki (stała zamiast szerokości
+/// terminala) i banera (brak zamiast `presentations/banner.txt`).
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    frame_width: usize,
+    responsive: bool,
+    palette: ThemePalette,
+    theme_label: String,
+    builtin_theme: Option<ThemeName>,
+    theme_path: Option<PathBuf>,
+    watch_theme: bool,
+    banner_path: Option<PathBuf>,
+    banner_width: Option<usize>,
+    sticky_banner: bool,
+    presentation_title: String,
+    animations_enabled: bool,
+    typing_cursor: Option<TypingCursor>,
+    easing: Easing,
+    compact_footer: bool,
+    no_color: bool,
+    compact_mode: bool,
+    wrap: bool,
+    deck_info: bool,
+    footer_fields: Vec<FooterField>,
+    corner_number: Option<String>,
+    position_fallback: PositionFallback,
+    line_spacing: usize,
+    heading_spacing: usize,
+    time_format: String,
+    on_slide: Option<String>,
+    highlight: Vec<String>,
+    trace_events: bool,
+    force_reload: bool,
+    key_repeat_guard: Duration,
+    idle_dim: Duration,
+    auto_advance: Duration,
+    loop_playback: bool,
+    progress_bar: bool,
+    vcenter: bool,
+    alt_screen: bool,
+    set_title: bool,
+    hyperlinks: bool,
+    total_budget: Option<Duration>,
+    presenter_view: bool,
+    enter_action: EnterAction,
+    snapshot_dir: Option<PathBuf>,
+    notes_out: Option<PathBuf>,
+    syntax_highlight: bool,
+    watch_once: bool,
+    banner_gradient: bool,
+    banner_color_cycle: bool,
+    upcase_headings: bool,
+    replay: Option<PathBuf>,
+    pause_at: Vec<usize>,
+    background: Background,
+    contrast_boost: Option<f32>,
+    heading_banner: bool,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self {
+            frame_width: DEFAULT_FRAME_WIDTH,
+            responsive: false,
+            palette: ThemeName::Neon.defaults(Background::Dark),
+            theme_label: ThemeName::Neon.to_string(),
+            builtin_theme: Some(ThemeName::Neon),
+            theme_path: None,
+            watch_theme: false,
+            banner_path: None,
+            banner_width: None,
+            sticky_banner: false,
+            presentation_title: "Rust Lab Terminal".to_string(),
+            animations_enabled: true,
+            typing_cursor: None,
+            easing: Easing::default(),
+            compact_footer: false,
+            no_color: false,
+            compact_mode: false,
+            wrap: false,
+            deck_info: false,
+            footer_fields: FooterField::all().to_vec(),
+            corner_number: None,
+            position_fallback: PositionFallback::default(),
+            line_spacing: 0,
+            heading_spacing: 0,
+            time_format: "%H:%M:%S".to_string(),
+            on_slide: None,
+            highlight: Vec::new(),
+            trace_events: false,
+            force_reload: false,
+            key_repeat_guard: Duration::ZERO,
+            idle_dim: Duration::ZERO,
+            auto_advance: Duration::ZERO,
+            loop_playback: false,
+            progress_bar: false,
+            vcenter: false,
+            alt_screen: false,
+            set_title: true,
+            hyperlinks: true,
+            total_budget: None,
+            presenter_view: false,
+            enter_action: EnterAction::default(),
+            snapshot_dir: None,
+            notes_out: None,
+            syntax_highlight: false,
+            watch_once: false,
+            banner_gradient: false,
+            banner_color_cycle: false,
+            upcase_headings: true,
+            replay: None,
+            pause_at: Vec::new(),
+            background: Background::Dark,
+            contrast_boost: None,
+            heading_banner: false,
+        }
+    }
+}
+
+impl ConfigBuilder {
+    pub fn frame_width(mut self, frame_width: usize) -> Self {
+        self.frame_width = frame_width;
+        self
+    }
+
+    pub fn responsive(mut self, responsive: bool) -> Self {
+        self.responsive = responsive;
+        self
+    }
+
+    pub fn builtin_theme(mut self, theme: ThemeName) -> Self {
+        self.palette = theme.defaults(self.background);
+        self.theme_label = theme.to_string();
+        self.builtin_theme = Some(theme);
+        self
+    }
+
+    pub fn theme(mut self, label: impl Into<String>, palette: ThemePalette) -> Self {
+        self.palette = palette;
+        self.theme_label = label.into();
+        self.builtin_theme = None;
+        self
+    }
+
+    pub fn palette(mut self, palette: ThemePalette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    pub fn theme_path(mut self, theme_path: Option<PathBuf>, watch: bool) -> Self {
+        self.theme_path = theme_path;
+        self.watch_theme = watch;
+        self
+    }
+
+    pub fn banner(mut self, banner_path: Option<PathBuf>, sticky: bool) -> Self {
+        self.banner_path = banner_path;
+        self.sticky_banner = sticky;
+        self
+    }
+
+    pub fn banner_gradient(mut self, gradient: bool) -> Self {
+        self.banner_gradient = gradient;
+        self
+    }
+
+    pub fn banner_color_cycle(mut self, cycle: bool) -> Self {
+        self.banner_color_cycle = cycle;
+        self
+    }
+
+    pub fn upcase_headings(mut self, upcase: bool) -> Self {
+        self.upcase_headings = upcase;
+        self
+    }
+
+    pub fn heading_banner(mut self, banner: bool) -> Self {
+        self.heading_banner = banner;
+        self
+    }
+
+    pub fn contrast_boost(mut self, factor: Option<f32>) -> Self {
+        self.contrast_boost = factor;
+        self
+    }
+
+    pub fn background(mut self, background: Background) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn replay(mut self, log: Option<PathBuf>) -> Self {
+        self.replay = log;
+        self
+    }
+
+    pub fn pause_at(mut self, numbers: Vec<usize>) -> Self {
+        self.pause_at = numbers;
+        self
+    }
+
+    pub fn banner_width(mut self, banner_width: Option<usize>) -> Self {
+        self.banner_width = banner_width;
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.presentation_title = title.into();
+        self
+    }
+
+    pub fn animations(mut self, enabled: bool) -> Self {
+        self.animations_enabled = enabled;
+        self
+    }
+
+    pub fn typing_cursor(mut self, cursor: Option<TypingCursor>) -> Self {
+        self.typing_cursor = cursor;
+        self
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn compact_footer(mut self, compact: bool) -> Self {
+        self.compact_footer = compact;
+        self
+    }
+
+    pub fn no_color(mut self, no_color: bool) -> Self {
+        self.no_color = no_color;
+        self
+    }
+
+    pub fn compact_mode(mut self, compact: bool) -> Self {
+        self.compact_mode = compact;
+        self
+    }
+
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn deck_info(mut self, info: bool) -> Self {
+        self.deck_info = info;
+        self
+    }
+
+    pub fn footer_fields(mut self, fields: Vec<FooterField>) -> Self {
+        self.footer_fields = fields;
+        self
+    }
+
+    pub fn corner_number(mut self, format: Option<String>) -> Self {
+        self.corner_number = format;
+        self
+    }
+
+    pub fn position_fallback(mut self, fallback: PositionFallback) -> Self {
+        self.position_fallback = fallback;
+        self
+    }
+
+    pub fn spacing(mut self, line_spacing: usize, heading_spacing: usize) -> Self {
+        self.line_spacing = line_spacing;
+        self.heading_spacing = heading_spacing;
+        self
+    }
+
+    pub fn time_format(mut self, time_format: impl Into<String>) -> Self {
+        self.time_format = time_format.into();
+        self
+    }
+
+    pub fn on_slide(mut self, command: Option<String>) -> Self {
+        self.on_slide = command;
+        self
+    }
+
+    pub fn highlight(mut self, words: Vec<String>) -> Self {
+        self.highlight = words;
+        self
+    }
+
+    pub fn trace_events(mut self, trace: bool) -> Self {
+        self.trace_events = trace;
+        self
+    }
+
+    pub fn force_reload(mut self, force: bool) -> Self {
+        self.force_reload = force;
+        self
+    }
+
+    pub fn key_repeat_guard(mut self, window: Duration) -> Self {
+        self.key_repeat_guard = window;
+        self
+    }
+
+    pub fn idle_dim(mut self, after: Duration) -> Self {
+        self.idle_dim = after;
+        self
+    }
+
+    pub fn auto_advance(mut self, every: Duration) -> Self {
+        self.auto_advance = every;
+        self
+    }
+
+    pub fn loop_playback(mut self, enabled: bool) -> Self {
+        self.loop_playback = enabled;
+        self
+    }
+
+    pub fn progress_bar(mut self, enabled: bool) -> Self {
+        self.progress_bar = enabled;
+        self
+    }
+
+    pub fn vcenter(mut self, enabled: bool) -> Self {
+        self.vcenter = enabled;
+        self
+    }
+
+    pub fn alt_screen(mut self, enabled: bool) -> Self {
+        self.alt_screen = enabled;
+        self
+    }
+
+    pub fn set_title(mut self, enabled: bool) -> Self {
+        self.set_title = enabled;
+        self
+    }
+
+    pub fn hyperlinks(mut self, enabled: bool) -> Self {
+        self.hyperlinks = enabled;
+        self
+    }
+
+    pub fn total_budget(mut self, budget: Option<Duration>) -> Self {
+        self.total_budget = budget;
+        self
+    }
+
+    pub fn presenter_view(mut self, enabled: bool) -> Self {
+        self.presenter_view = enabled;
+        self
+    }
+
+    pub fn enter_action(mut self, action: EnterAction) -> Self {
+        self.enter_action = action;
+        self
+    }
+
+    pub fn snapshot_dir(mut self, directory: Option<PathBuf>) -> Self {
+        self.snapshot_dir = directory;
+        self
+    }
+
+    pub fn notes_out(mut self, path: Option<PathBuf>) -> Self {
+        self.notes_out = path;
+        self
+    }
+
+    pub fn syntax_highlight(mut self, highlight: bool) -> Self {
+        self.syntax_highlight = highlight;
+        self
+    }
+
+    pub fn watch_once(mut self, once: bool) -> Self {
+        self.watch_once = once;
+        self
+    }
+
+    pub fn build(self) -> Result<Config, Box<dyn std::error::Error>> {
+        clock::validate_format(&self.time_format)?;
+        if let Some(factor) = self.contrast_boost
+            && !(MIN_CONTRAST_BOOST..=MAX_CONTRAST_BOOST).contains(&factor)
+        {
+            return Err(format!(
+                "Wzmocnienie kontrastu musi mieścić się w zakresie {:.1}–{:.1} (podano {})",
+                MIN_CONTRAST_BOOST, MAX_CONTRAST_BOOST, factor
+            )
+            .into());
+        }
+        let palette = match self.contrast_boost {
+            Some(factor) => self.palette.with_contrast(factor),
+            None => self.palette,
+        };
+        let sticky_banner = match self.banner_path.as_deref() {
+            Some(path) if self.sticky_banner => Some(load_banner(path)?),
+            _ => None,
+        };
+        let replay = match self.replay.as_deref() {
+            Some(path) => replay::load(path)?,
+            None => Vec::new(),
+        };
+
+        Ok(Config {
+            frame_width: self.frame_width,
+            responsive: self.responsive,
+            palette,
+            theme_path: self.theme_path,
+            watch_theme: self.watch_theme,
+            banner_path: self.banner_path,
+            banner_width: self.banner_width,
+            sticky_banner,
+            printed_banner: None,
+            presentation_title: self.presentation_title,
+            theme_label: self.theme_label,
+            builtin_theme: self.builtin_theme,
+            animations_enabled: self.animations_enabled,
+            typing_cursor: self.typing_cursor,
+            easing: self.easing,
+            compact_footer: self.compact_footer,
+            no_color: self.no_color,
+            compact_mode: self.compact_mode,
+            wrap: self.wrap,
+            deck_info: self.deck_info,
+            footer_fields: self.footer_fields,
+            corner_number: self.corner_number,
+            position_fallback: self.position_fallback,
+            line_spacing: self.line_spacing,
+            heading_spacing: self.heading_spacing,
+            time_format: self.time_format,
+            on_slide: self.on_slide,
+            highlight: highlight_pattern(&self.highlight)?,
+            trace_events: self.trace_events,
+            force_reload: self.force_reload,
+            key_repeat_guard: self.key_repeat_guard,
+            idle_dim: self.idle_dim,
+            auto_advance: self.auto_advance,
+            loop_playback: self.loop_playback,
+            progress_bar: self.progress_bar,
+            vcenter: self.vcenter,
+            alt_screen: self.alt_screen,
+            set_title: self.set_title,
+            hyperlinks: self.hyperlinks,
+            total_budget: self.total_budget,
+            presenter_view: self.presenter_view,
+            enter_action: self.enter_action,
+            snapshot_dir: self.snapshot_dir,
+            notes_out: self.notes_out,
+            syntax_highlight: self.syntax_highlight,
+            watch_once: self.watch_once,
+            banner_gradient: self.banner_gradient,
+            banner_color_cycle: self.banner_color_cycle,
+            upcase_headings: self.upcase_headings,
+            replay,
+            pause_at: self.pause_at,
+            background: self.background,
+            contrast_boost: self.contrast_boost,
+            heading_banner: self.heading_banner,
+        })
+    }
+}
+
+impl Config {
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+}
@@ -0,0 +1,56 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+
+const MODIFIED_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+pub(crate) struct DeckInfo {
+    pub(crate) modified: Option<String>,
+    pub(crate) version: &'static str,
+    pub(crate) commit: Option<String>,
+}
+
+pub(crate) fn collect(scripts: &[PathBuf]) -> DeckInfo {
+    let modified = scripts
+        .iter()
+        .filter_map(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+        .max()
+        .map(|time| {
+            DateTime::<Local>::from(time)
+                .format(MODIFIED_FORMAT)
+                .to_string()
+        });
+    let commit = scripts
+        .first()
+        .and_then(|path| path.parent())
+        .and_then(git_commit);
+
+    DeckInfo {
+        modified,
+        version: env!("CARGO_PKG_VERSION"),
+        commit,
+    }
+}
+
+#[cfg(feature = "git")]
+fn git_commit(dir: &Path) -> Option<String> {
+    use std::process::{Command, Stdio};
+
+    let dir = Some(dir).filter(|dir| !dir.as_os_str().is_empty());
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir.unwrap_or(Path::new(".")))
+        .args(["rev-parse", "--short", "HEAD"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let commit = String::from_utf8(output.stdout).ok()?;
+    Some(commit.trim().to_string()).filter(|commit| output.status.success() && !commit.is_empty())
+}
+
+#[cfg(not(feature = "git"))]
+fn git_commit(_dir: &Path) -> Option<String> {
+    None
+}
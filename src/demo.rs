@@ -0,0 +1,144 @@
+const WORDS: &[&str] = &[
+    "terminal",
+    "ramka",
+    "sekwencja",
+    "motyw",
+    "kursor",
+    "sygnał",
+    "wektor",
+    "kineskop",
+    "neon",
+    "rdzeń",
+    "pamięć",
+    "moduł",
+    "kompilator",
+    "bufor",
+    "zegar",
+    "strumień",
+    "kanał",
+    "obwód",
+    "laboratorium",
+    "prototyp",
+];
+const MAX_SPACER_ROWS: u64 = 3;
+
+pub(crate) struct Lcg(pub(crate) u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    pub(crate) fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+
+    fn sentence(&mut self) -> String {
+        let count = 3 + self.below(4);
+        let words: Vec<&str> = (0..count)
+            .map(|_| WORDS[self.below(WORDS.len() as u64) as usize])
+            .collect();
+        let text = words.join(" ");
+        let mut chars = text.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => text,
+        }
+    }
+}
+
+/// Przykładowy skrypt z `count` liniami treści, kolejno obejmującymi każdy
+/// wbudowany rodzaj linii, podzielony `---` na slajdy po osiem linii (style
+/// z `--rules` wymagają własnego pliku reguł).
+pub(crate) fn generate(count: usize, seed: u64) -> String {
+    let mut rng = Lcg(seed);
+    let mut script = String::new();
+    for position in 0..count {
+        let line = match position % 8 {
+            0 => {
+                if position > 0 {
+                    script.push_str("---\n");
+                }
+                script.push_str(&format!("@frame-title Demo {}\n", position / 8 + 1));
+                format!("# {}", rng.sentence())
+            }
+            1 => format!("- {}", rng.sentence()),
+            2 => format!("> {}", rng.sentence()),
+            3 => rng.sentence(),
+            4 => format!(
+                "- [{}] {}",
+                if rng.below(2) == 0 { ' ' } else { 'x' },
+                rng.sentence()
+            ),
+            5 => "===".to_string(),
+            6 => format!("@space {}", 1 + rng.below(MAX_SPACER_ROWS)),
+            _ => format!("@instant {}", rng.sentence()),
+        };
+        script.push_str(&line);
+        script.push('\n');
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::RuleSet;
+    use crate::{SegmentKind, SegmentParser};
+
+    #[test]
+    fn same_seed_gives_same_deck() {
+        assert_eq!(generate(12, 7), generate(12, 7));
+        assert_ne!(generate(12, 7), generate(12, 8));
+    }
+
+    #[test]
+    fn generated_deck_round_trips_through_parser() {
+        let rules = RuleSet::default();
+        let script = generate(16, 42);
+        let segments: Vec<_> = SegmentParser::new(script.as_bytes(), &rules)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(segments.len(), 17);
+        let kinds: Vec<&str> = segments
+            .iter()
+            .map(|segment| match segment.kind() {
+                SegmentKind::Heading(_) => "heading",
+                SegmentKind::Bullet { .. } => "bullet",
+                SegmentKind::Callout(_) => "callout",
+                SegmentKind::Quote(_) => "quote",
+                SegmentKind::Table { .. } => "table",
+                SegmentKind::Contents { .. } => "contents",
+                SegmentKind::Plain(_) => "plain",
+                SegmentKind::Task { .. } => "task",
+                SegmentKind::Numbered { .. } => "numbered",
+                SegmentKind::Separator => "separator",
+                SegmentKind::SlideBreak => "slide-break",
+                SegmentKind::Code { .. } => "code",
+                SegmentKind::Spacer(_) => "spacer",
+                SegmentKind::Notes(_) => "notes",
+                SegmentKind::Custom { .. } => "custom",
+            })
+            .collect();
+        assert_eq!(
+            kinds[..8],
+            [
+                "heading",
+                "bullet",
+                "callout",
+                "plain",
+                "task",
+                "separator",
+                "spacer",
+                "plain"
+            ]
+        );
+        assert_eq!(kinds[8], "slide-break");
+        assert_eq!(segments[9].frame_title(), Some("Demo 2"));
+    }
+}
@@ -0,0 +1,543 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::interaction::Slide;
+use crate::span::{Span, char_width, fit_spans, plain_text};
+use crate::table::table_lines;
+use crate::theme;
+use crate::{CONTENTS_LEADER, Config, QUOTE_PREFIX, SegmentKind, bullet_glyph, code_row};
+
+const PDF_RENDERER: &str = "wkhtmltopdf";
+const PAGE_WIDTH: &str = "254mm";
+const PAGE_HEIGHT: &str = "142.875mm";
+const SVG_FONT_SIZE: f32 = 16.0;
+const SVG_CELL_WIDTH: f32 = 9.6;
+const SVG_ROW_HEIGHT: f32 = 22.0;
+const SVG_PADDING: f32 = 24.0;
+
+pub(crate) fn export_pdf(
+    config: &Config,
+    slides: &[Slide],
+    output: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = &config.with_colors();
+    let html_path =
+        std::env::temp_dir().join(format!("presentation-export-{}.html", std::process::id()));
+    fs::write(&html_path, render_html(config, slides))?;
+
+    let status = Command::new(PDF_RENDERER)
+        .arg("--quiet")
+        .args(["--page-width", PAGE_WIDTH, "--page-height", PAGE_HEIGHT])
+        .args(["--margin-top", "0", "--margin-bottom", "0"])
+        .args(["--margin-left", "0", "--margin-right", "0"])
+        .arg(&html_path)
+        .arg(output)
+        .stdin(Stdio::null())
+        .status();
+    let _ = fs::remove_file(&html_path);
+
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!(
+            "{} zakończył działanie z błędem ({}) podczas eksportu do {}",
+            PDF_RENDERER,
+            status,
+            output.display()
+        )
+        .into()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Err(format!(
+            "Eksport do PDF wymaga programu {} – zainstaluj go i upewnij się, że jest w PATH",
+            PDF_RENDERER
+        )
+        .into()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+pub(crate) fn export_svg(
+    config: &Config,
+    slides: &[Slide],
+    directory: &Path,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let config = &config.with_colors();
+    fs::create_dir_all(directory)?;
+    for (index, slide) in slides.iter().enumerate() {
+        let themed = slide
+            .theme()
+            .and_then(Result::ok)
+            .map(|theme| config.themed(theme));
+        let config = themed.as_ref().unwrap_or(config);
+        let path = directory.join(format!("slide-{:03}.svg", index + 1));
+        fs::write(&path, render_svg(config, slide, index, slides.len()))?;
+    }
+    Ok(slides.len())
+}
+
+fn render_svg(config: &Config, slide: &Slide, index: usize, total: usize) -> String {
+    let dim = css_color(config.color_dim()).unwrap_or_else(|| "#444444".to_string());
+    let glow = css_color(config.color_glow()).unwrap_or_else(|| "#00ffff".to_string());
+    let columns = config.frame_width().saturating_sub(4);
+
+    let mut body = String::new();
+    let mut row = 0;
+    for segment in slide.segments() {
+        let y = svg_row_y(row);
+        match segment.kind() {
+            SegmentKind::Spacer(rows) => {
+                row += rows;
+                continue;
+            }
+            SegmentKind::Code { lines, .. } => {
+                for (offset, line) in lines.iter().enumerate() {
+                    let fitted = code_row(line, columns);
+                    let y = svg_row_y(row + offset);
+                    body.push_str(&svg_text(config, &fitted.spans, columns, &dim, "", y));
+                }
+                row += lines.len();
+                continue;
+            }
+            SegmentKind::Quote(rows) => {
+                for (offset, spans) in rows.iter().enumerate() {
+                    let y = svg_row_y(row + offset);
+                    let italic = " font-style=\"italic\"";
+                    body.push_str(&svg_text(config, &quote_row(spans), columns, &glow, italic, y));
+                }
+                row += rows.len();
+                continue;
+            }
+            SegmentKind::Table { headers, rows } => {
+                let accent = css_color(config.color_accent()).unwrap_or_else(|| "#ffaf00".to_string());
+                let lines = table_lines(headers, rows, columns);
+                for (offset, line) in lines.iter().enumerate() {
+                    let y = svg_row_y(row + offset);
+                    body.push_str(&svg_text(config, line, columns, &accent, "", y));
+                }
+                row += lines.len();
+                continue;
+            }
+            SegmentKind::Separator | SegmentKind::SlideBreak => body.push_str(&format!(
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"2\"/>\n",
+                svg_column_x(0),
+                y - SVG_FONT_SIZE / 3.0,
+                svg_column_x(columns),
+                y - SVG_FONT_SIZE / 3.0,
+                dim
+            )),
+            kind => {
+                let (spans, color, attributes) = svg_line(config, kind);
+                body.push_str(&svg_text(config, &spans, columns, &color, &attributes, y));
+            }
+        }
+        row += 1;
+    }
+
+    let frame_width = config.frame_width() as f32 * SVG_CELL_WIDTH;
+    let frame_height = (row.max(1) + 2) as f32 * SVG_ROW_HEIGHT;
+    let width = frame_width + 2.0 * SVG_PADDING;
+    let height = frame_height + SVG_ROW_HEIGHT + 2.0 * SVG_PADDING;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" xml:space=\"preserve\" width=\"{width:.0}\" height=\"{height:.0}\" \
+         viewBox=\"0 0 {width:.0} {height:.0}\" font-family=\"monospace\" font-size=\"{font}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"#000\"/>\n\
+         <rect x=\"{pad}\" y=\"{pad}\" width=\"{frame_width:.1}\" height=\"{frame_height:.1}\" rx=\"10\" \
+         fill=\"none\" stroke=\"{dim}\" stroke-width=\"2\"/>\n",
+        width = width,
+        height = height,
+        font = SVG_FONT_SIZE,
+        pad = SVG_PADDING,
+        frame_width = frame_width,
+        frame_height = frame_height,
+        dim = dim,
+    );
+    if let Some(title) = slide.frame_title() {
+        let fitted = fit_spans(&[Span::plain(title)], columns.saturating_sub(4));
+        svg.push_str(&format!(
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#000\"/>\n\
+             <text x=\"{:.1}\" y=\"{:.1}\" fill=\"{}\">{}</text>\n",
+            svg_column_x(1),
+            SVG_PADDING - SVG_ROW_HEIGHT / 2.0,
+            (fitted.width + 2) as f32 * SVG_CELL_WIDTH,
+            SVG_ROW_HEIGHT,
+            svg_column_x(2),
+            SVG_PADDING + SVG_FONT_SIZE / 3.0,
+            glow,
+            escape_html(&plain_text(&fitted.spans))
+        ));
+    }
+    svg.push_str(&body);
+    svg.push_str(&format!(
+        "<text x=\"{:.1}\" y=\"{:.1}\" fill=\"{}\">{} :: {}/{}</text>\n</svg>\n",
+        SVG_PADDING,
+        height - SVG_PADDING,
+        dim,
+        escape_html(config.presentation_title()),
+        index + 1,
+        total
+    ));
+    svg
+}
+
+/// Treść linii sekwencji wraz z kolorem i stylem, wspólna dla eksportu
+/// do SVG i migawek PNG.
+pub(crate) struct StyledLine<'a> {
+    pub(crate) spans: Vec<Span>,
+    pub(crate) color: &'a str,
+    pub(crate) bold: bool,
+    pub(crate) italic: bool,
+    pub(crate) underline: bool,
+}
+
+impl<'a> StyledLine<'a> {
+    pub(crate) fn new(spans: Vec<Span>, color: &'a str) -> Self {
+        Self {
+            spans,
+            color,
+            bold: false,
+            italic: false,
+            underline: false,
+        }
+    }
+}
+
+pub(crate) fn styled_line<'a>(config: &'a Config, kind: &SegmentKind) -> StyledLine<'a> {
+    let glyphs = config.glyphs();
+    match kind {
+        SegmentKind::Heading(spans) => StyledLine {
+            bold: true,
+            underline: true,
+            ..StyledLine::new(
+                with_glyphs(
+                    glyphs.heading_prefix(),
+                    &[Span::plain(config.upcase(&plain_text(spans)))],
+                    "",
+                ),
+                config.color_glow(),
+            )
+        },
+        SegmentKind::Bullet { indent, text } => StyledLine::new(
+            with_glyphs(&bullet_glyph(glyphs, *indent), text, ""),
+            config.color_accent(),
+        ),
+        SegmentKind::Callout(spans) => StyledLine {
+            italic: true,
+            ..StyledLine::new(
+                with_glyphs(glyphs.callout_open(), spans, glyphs.callout_close()),
+                config.color_glow(),
+            )
+        },
+        SegmentKind::Custom { style, text } => StyledLine {
+            bold: style.bold(),
+            italic: style.italic(),
+            underline: style.underline(),
+            ..StyledLine::new(
+                with_glyphs(style.marker().unwrap_or(""), text, ""),
+                config.color(style.color()),
+            )
+        },
+        SegmentKind::Numbered { number, text } => StyledLine::new(
+            with_glyphs(&format!("{}.", number), text, ""),
+            config.color_accent(),
+        ),
+        SegmentKind::Task { done: false, text } => StyledLine::new(
+            with_glyphs(glyphs.task_open(), text, ""),
+            config.color_dim(),
+        ),
+        SegmentKind::Task { done: true, text } => StyledLine::new(
+            with_glyphs(glyphs.task_done(), text, ""),
+            config.color_glow(),
+        ),
+        SegmentKind::Contents { title, slide } => StyledLine::new(
+            with_glyphs("", title, &format!("{} {:02}", CONTENTS_LEADER, slide)),
+            config.color_accent(),
+        ),
+        SegmentKind::Plain(spans) => StyledLine::new(spans.clone(), config.color_accent()),
+        SegmentKind::Separator
+        | SegmentKind::SlideBreak
+        | SegmentKind::Spacer(_)
+        | SegmentKind::Code { .. }
+        | SegmentKind::Quote(_)
+        | SegmentKind::Table { .. }
+        | SegmentKind::Notes(_) => StyledLine::new(Vec::new(), config.color_dim()),
+    }
+}
+
+/// Wiersz cytatu w eksporcie: wcięcie i pionowa kreska jak w terminalu.
+pub(crate) fn quote_row(spans: &[Span]) -> Vec<Span> {
+    std::iter::once(Span::plain(QUOTE_PREFIX))
+        .chain(spans.iter().cloned())
+        .collect()
+}
+
+fn svg_line(config: &Config, kind: &SegmentKind) -> (Vec<Span>, String, String) {
+    let line = styled_line(config, kind);
+    let mut attributes = String::new();
+    if line.bold {
+        attributes.push_str(" font-weight=\"bold\"");
+    }
+    if line.italic {
+        attributes.push_str(" font-style=\"italic\"");
+    }
+    if line.underline {
+        attributes.push_str(" text-decoration=\"underline\"");
+    }
+    let color = css_color(line.color).unwrap_or_else(|| "#ffaf00".to_string());
+    (line.spans, color, attributes)
+}
+
+fn with_glyphs(open: &str, spans: &[Span], close: &str) -> Vec<Span> {
+    let open = Some(open)
+        .filter(|glyph| !glyph.is_empty())
+        .map(|glyph| Span::plain(format!("{} ", glyph)));
+    let close = Some(close)
+        .filter(|glyph| !glyph.is_empty())
+        .map(|glyph| Span::plain(format!(" {}", glyph)));
+    open.into_iter()
+        .chain(spans.iter().cloned())
+        .chain(close)
+        .collect()
+}
+
+fn svg_text(
+    config: &Config,
+    spans: &[Span],
+    columns: usize,
+    color: &str,
+    attributes: &str,
+    y: f32,
+) -> String {
+    let fitted = fit_spans(spans, columns);
+    let mut text = format!("<text y=\"{:.1}\" fill=\"{}\"{}>", y, color, attributes);
+    let mut column = 0;
+    for span in &fitted.spans {
+        let style = span.style();
+        let mut span_attributes = String::new();
+        if let Some(fill) = style.color.and_then(|slot| css_color(config.color(slot))) {
+            span_attributes.push_str(&format!(" fill=\"{}\"", fill));
+        }
+        if style.bold {
+            span_attributes.push_str(" font-weight=\"bold\"");
+        }
+        if style.italic {
+            span_attributes.push_str(" font-style=\"italic\"");
+        }
+        if style.underline {
+            span_attributes.push_str(" text-decoration=\"underline\"");
+        }
+
+        let mut run = String::new();
+        let mut run_start = column;
+        for ch in span.text().chars() {
+            let width = char_width(ch);
+            if width > 1 {
+                text.push_str(&svg_tspan(&run, run_start, &span_attributes));
+                text.push_str(&svg_tspan(&ch.to_string(), column, &span_attributes));
+                run.clear();
+                column += width;
+                run_start = column;
+                continue;
+            }
+            run.push(ch);
+            column += width;
+        }
+        text.push_str(&svg_tspan(&run, run_start, &span_attributes));
+    }
+    if fitted.truncated {
+        text.push_str(&svg_tspan("›", column, ""));
+    }
+    text.push_str("</text>\n");
+    text
+}
+
+fn svg_tspan(text: &str, column: usize, attributes: &str) -> String {
+    if text.is_empty() {
+        return String::new();
+    }
+    format!(
+        "<tspan x=\"{:.1}\"{}>{}</tspan>",
+        svg_column_x(column),
+        attributes,
+        escape_html(text)
+    )
+}
+
+fn svg_column_x(column: usize) -> f32 {
+    SVG_PADDING + (column + 2) as f32 * SVG_CELL_WIDTH
+}
+
+fn svg_row_y(row: usize) -> f32 {
+    SVG_PADDING + (row + 1) as f32 * SVG_ROW_HEIGHT + SVG_FONT_SIZE
+}
+
+fn render_html(config: &Config, slides: &[Slide]) -> String {
+    let accent = css_color(config.color_accent()).unwrap_or_else(|| "#ffaf00".to_string());
+    let dim = css_color(config.color_dim()).unwrap_or_else(|| "#444444".to_string());
+    let glow = css_color(config.color_glow()).unwrap_or_else(|| "#00ffff".to_string());
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"pl\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n\
+         html, body {{ margin: 0; padding: 0; background: #000; }}\n\
+         section {{ box-sizing: border-box; width: {width}; height: {height}; padding: 12mm; \
+         page-break-after: always; background: #000; color: {accent}; \
+         font-family: monospace; font-size: 20pt; border: 2px solid {dim}; \
+         display: flex; flex-direction: column; justify-content: center; \
+         overflow-wrap: anywhere; }}\n\
+         section:last-child {{ page-break-after: auto; }}\n\
+         .heading {{ color: {glow}; font-weight: bold; text-decoration: underline; }}\n\
+         .callout {{ color: {glow}; font-style: italic; }}\n\
+         .open {{ color: {dim}; }}\n\
+         .done {{ color: {glow}; }}\n\
+         footer {{ margin-top: auto; color: {dim}; font-size: 10pt; }}\n\
+         hr {{ width: 100%; border: 0; border-top: 2px solid {dim}; }}\n\
+         pre {{ margin: 0; color: {dim}; font-size: 16pt; overflow-x: auto; \
+         overflow-wrap: normal; }}\n\
+         </style>\n</head>\n<body>\n",
+        title = escape_html(config.presentation_title()),
+        width = PAGE_WIDTH,
+        height = PAGE_HEIGHT,
+        accent = accent,
+        dim = dim,
+        glow = glow,
+    );
+
+    for (index, slide) in slides.iter().enumerate() {
+        html.push_str("<section>\n");
+        for segment in slide.segments() {
+            html.push_str(&render_segment(config, segment.kind()));
+        }
+        html.push_str(&format!(
+            "<footer>{} :: {}/{}</footer>\n</section>\n",
+            escape_html(config.presentation_title()),
+            index + 1,
+            slides.len()
+        ));
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn render_segment(config: &Config, kind: &SegmentKind) -> String {
+    let glyphs = config.glyphs();
+    match kind {
+        SegmentKind::Heading(spans) => format!(
+            "<h1 class=\"heading\">{}{}</h1>\n",
+            glyph_prefix(glyphs.heading_prefix()),
+            escape_html(&config.upcase(&plain_text(spans)))
+        ),
+        SegmentKind::Bullet { indent: 0, text } => format!(
+            "<p>{}{}</p>\n",
+            glyph_prefix(glyphs.bullet()),
+            escape_html(&plain_text(text))
+        ),
+        SegmentKind::Bullet { indent, text } => format!(
+            "<p style=\"margin-left: {}em\">{}{}</p>\n",
+            indent,
+            glyph_prefix(bullet_glyph(glyphs, *indent).trim_start()),
+            escape_html(&plain_text(text))
+        ),
+        SegmentKind::Callout(spans) => format!(
+            "<p class=\"callout\">{}{}{}</p>\n",
+            glyph_prefix(glyphs.callout_open()),
+            escape_html(&plain_text(spans)),
+            glyph_suffix(glyphs.callout_close())
+        ),
+        SegmentKind::Quote(rows) => format!(
+            "<blockquote>{}</blockquote>\n",
+            rows.iter()
+                .map(|spans| escape_html(&plain_text(spans)))
+                .collect::<Vec<_>>()
+                .join("<br>")
+        ),
+        SegmentKind::Table { headers, rows } => {
+            let cells = |cells: &[Vec<Span>], tag: &str| -> String {
+                cells
+                    .iter()
+                    .map(|cell| format!("<{tag}>{}</{tag}>", escape_html(&plain_text(cell))))
+                    .collect()
+            };
+            let body: String = rows
+                .iter()
+                .map(|row| format!("<tr>{}</tr>", cells(row, "td")))
+                .collect();
+            format!(
+                "<table><thead><tr>{}</tr></thead><tbody>{}</tbody></table>\n",
+                cells(headers, "th"),
+                body
+            )
+        }
+        SegmentKind::Contents { title, slide } => format!(
+            "<p>{} {} {:02}</p>\n",
+            escape_html(&plain_text(title)),
+            CONTENTS_LEADER,
+            slide
+        ),
+        SegmentKind::Plain(spans) => format!("<p>{}</p>\n", escape_html(&plain_text(spans))),
+        SegmentKind::Custom { style, text } => format!(
+            "<p>{}{}</p>\n",
+            style
+                .marker()
+                .map(|marker| format!("{} ", escape_html(marker)))
+                .unwrap_or_default(),
+            escape_html(&plain_text(text))
+        ),
+        SegmentKind::Numbered { number, text } => {
+            format!("<p>{}. {}</p>\n", number, escape_html(&plain_text(text)))
+        }
+        SegmentKind::Task { done: false, text } => format!(
+            "<p class=\"open\">{}{}</p>\n",
+            glyph_prefix(glyphs.task_open()),
+            escape_html(&plain_text(text))
+        ),
+        SegmentKind::Task { done: true, text } => format!(
+            "<p class=\"done\">{}{}</p>\n",
+            glyph_prefix(glyphs.task_done()),
+            escape_html(&plain_text(text))
+        ),
+        SegmentKind::Separator | SegmentKind::SlideBreak => "<hr>\n".to_string(),
+        SegmentKind::Spacer(rows) => format!("<div style=\"height: {}em\"></div>\n", rows),
+        SegmentKind::Code { language, lines } => format!(
+            "<pre><code{}>{}</code></pre>\n",
+            language
+                .as_deref()
+                .map(|language| format!(" class=\"language-{}\"", escape_html(language)))
+                .unwrap_or_default(),
+            escape_html(&lines.join("\n"))
+        ),
+        SegmentKind::Notes(_) => String::new(),
+    }
+}
+
+fn glyph_prefix(glyph: &str) -> String {
+    Some(glyph)
+        .filter(|glyph| !glyph.is_empty())
+        .map(|glyph| format!("{} ", escape_html(glyph)))
+        .unwrap_or_default()
+}
+
+fn glyph_suffix(glyph: &str) -> String {
+    Some(glyph)
+        .filter(|glyph| !glyph.is_empty())
+        .map(|glyph| format!(" {}", escape_html(glyph)))
+        .unwrap_or_default()
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn css_color(escape: &str) -> Option<String> {
+    let (red, green, blue) = theme::escape_rgb(escape)?;
+    Some(format!("#{:02x}{:02x}{:02x}", red, green, blue))
+}
@@ -0,0 +1,24 @@
+use std::process::{Command, Stdio};
+use std::thread;
+
+pub(crate) fn on_slide(command: &str, index: usize, heading: &str, notes: &[String]) {
+    let mut process = Command::new("sh");
+    process
+        .arg("-c")
+        .arg(command)
+        .arg("on-slide")
+        .arg((index + 1).to_string())
+        .arg(heading)
+        .env("PRESENTATION_SLIDE", (index + 1).to_string())
+        .env("PRESENTATION_HEADING", heading)
+        .env("PRESENTATION_NOTES", notes.join("\n"))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    thread::spawn(move || {
+        if let Ok(mut child) = process.spawn() {
+            let _ = child.wait();
+        }
+    });
+}
@@ -0,0 +1,190 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::Path;
+
+use image::imageops::{self, FilterType};
+
+use crate::theme::ThemePalette;
+
+const SAMPLE_SIZE: u32 = 64;
+const MIN_PIXELS: usize = 16;
+const MIN_SATURATION: f32 = 0.2;
+const MIN_DISTANCE: f32 = 60.0;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Rgb {
+    red: u8,
+    green: u8,
+    blue: u8,
+}
+
+impl Rgb {
+    pub(crate) fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+
+    fn escape(self) -> String {
+        format!("\x1b[38;2;{};{};{}m", self.red, self.green, self.blue)
+    }
+
+    fn lightness(self) -> f32 {
+        let max = self.red.max(self.green).max(self.blue);
+        let min = self.red.min(self.green).min(self.blue);
+        (f32::from(max) + f32::from(min)) / 510.0
+    }
+
+    fn saturation(self) -> f32 {
+        let max = f32::from(self.red.max(self.green).max(self.blue)) / 255.0;
+        let min = f32::from(self.red.min(self.green).min(self.blue)) / 255.0;
+        let lightness = (max + min) / 2.0;
+        if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        }
+    }
+
+    fn distance(self, other: Rgb) -> f32 {
+        let red = f32::from(self.red) - f32::from(other.red);
+        let green = f32::from(self.green) - f32::from(other.green);
+        let blue = f32::from(self.blue) - f32::from(other.blue);
+        (red * red + green * green + blue * blue).sqrt()
+    }
+
+    fn scaled(self, factor: f32) -> Rgb {
+        let scale = |channel: u8| (f32::from(channel) * factor).round().clamp(0.0, 255.0) as u8;
+        Rgb {
+            red: scale(self.red),
+            green: scale(self.green),
+            blue: scale(self.blue),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ImagePalette {
+    pub(crate) accent: Rgb,
+    pub(crate) dim: Rgb,
+    pub(crate) glow: Rgb,
+}
+
+impl ImagePalette {
+    pub(crate) fn palette(&self) -> ThemePalette {
+        ThemePalette::new(self.accent.escape(), self.dim.escape(), self.glow.escape())
+    }
+}
+
+pub(crate) fn derive(path: &Path) -> Result<Option<ImagePalette>, Box<dyn std::error::Error>> {
+    let image = image::open(path)
+        .map_err(|error| format!("Obraz ({}) nie został wczytany: {}", path.display(), error))?
+        .to_rgb8();
+    if (image.width() as usize) * (image.height() as usize) < MIN_PIXELS {
+        return Ok(None);
+    }
+    let sample = imageops::resize(&image, SAMPLE_SIZE, SAMPLE_SIZE, FilterType::Triangle);
+
+    let mut buckets: HashMap<(u8, u8, u8), (u32, [u32; 3])> = HashMap::new();
+    for pixel in sample.pixels() {
+        let [red, green, blue] = pixel.0;
+        let entry = buckets
+            .entry((red >> 4, green >> 4, blue >> 4))
+            .or_insert((0, [0; 3]));
+        entry.0 += 1;
+        entry.1[0] += u32::from(red);
+        entry.1[1] += u32::from(green);
+        entry.1[2] += u32::from(blue);
+    }
+
+    let mut colors: Vec<(u32, Rgb)> = buckets
+        .into_values()
+        .map(|(count, sums)| {
+            let average = |sum: u32| (sum / count) as u8;
+            (
+                count,
+                Rgb {
+                    red: average(sums[0]),
+                    green: average(sums[1]),
+                    blue: average(sums[2]),
+                },
+            )
+        })
+        .collect();
+    colors.sort_by_key(|(count, color)| (Reverse(*count), color.red, color.green, color.blue));
+
+    let vivid = |color: &Rgb| {
+        color.saturation() >= MIN_SATURATION && (0.2..=0.85).contains(&color.lightness())
+    };
+    let Some(accent) = colors.iter().map(|(_, color)| *color).find(vivid) else {
+        return Ok(None);
+    };
+    let glow = colors
+        .iter()
+        .map(|(_, color)| *color)
+        .filter(|color| vivid(color) && color.distance(accent) >= MIN_DISTANCE)
+        .max_by(|left, right| {
+            (left.lightness() + left.saturation())
+                .total_cmp(&(right.lightness() + right.saturation()))
+        })
+        .unwrap_or_else(|| accent.scaled(1.35));
+    let dim = colors
+        .iter()
+        .map(|(_, color)| *color)
+        .find(|color| {
+            color.saturation() < MIN_SATURATION && (0.15..=0.6).contains(&color.lightness())
+        })
+        .unwrap_or_else(|| accent.scaled(0.45));
+
+    Ok(Some(ImagePalette { accent, dim, glow }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb as Pixel, RgbImage};
+
+    /// Obraz zapisany jako PNG w katalogu tymczasowym; `paint` podaje kolor
+    /// piksela w kolumnie `x`.
+    fn palette_of(name: &str, size: u32, paint: impl Fn(u32) -> [u8; 3]) -> Option<ImagePalette> {
+        let path = std::env::temp_dir().join(format!(
+            "presentation-palette-{}-{}.png",
+            name,
+            std::process::id()
+        ));
+        RgbImage::from_fn(size, size, |x, _| Pixel(paint(x)))
+            .save(&path)
+            .unwrap();
+        let derived = derive(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        derived
+    }
+
+    #[test]
+    fn vivid_colors_become_accent_and_glow_and_gray_becomes_dim() {
+        let derived = palette_of("stripes", 64, |x| match x {
+            0..40 => [220, 40, 40],
+            40..56 => [40, 60, 220],
+            _ => [110, 110, 110],
+        })
+        .unwrap();
+
+        assert_eq!(derived.accent.hex(), "#dc2828");
+        assert_eq!(derived.glow.hex(), "#283cdc");
+        assert_eq!(derived.dim.hex(), "#6e6e6e");
+    }
+
+    #[test]
+    fn single_vivid_color_derives_glow_and_dim_from_the_accent() {
+        let derived = palette_of("single", 64, |_| [220, 40, 40]).unwrap();
+
+        assert_eq!(derived.accent.hex(), "#dc2828");
+        assert_eq!(derived.glow.hex(), "#ff3636");
+        assert_eq!(derived.dim.hex(), "#631212");
+    }
+
+    #[test]
+    fn tiny_or_monochrome_images_fall_back_to_the_builtin_theme() {
+        assert!(palette_of("tiny", 3, |_| [220, 40, 40]).is_none());
+        assert!(palette_of("gray", 64, |x| [x as u8 * 4; 3]).is_none());
+        assert!(palette_of("halves", 64, |x| if x < 32 { [0; 3] } else { [255; 3] }).is_none());
+    }
+}
@@ -1,64 +1,680 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::io::{self, Stdout, Write};
+use std::ops::Range;
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crossterm::ExecutableCommand;
 use crossterm::cursor;
 use crossterm::event::{self, Event, KeyCode};
 use crossterm::terminal::{self, Clear, ClearType};
 
+use crate::notes::{self, NotesOut};
+use crate::rules::ColorSlot;
+use crate::span::Span;
+use crate::watch::FileWatcher;
 use crate::{
-    Config, RESET, Segment, animate_line, print_frame_bottom, print_frame_top, transition_animation,
+    BANNER_CYCLE_STEP, BOLD, Config, EnterAction, FooterField, PositionFallback, RESET, Segment,
+    SegmentKind, SlideTheme, animate_line, banner_cycle_slot, clock, hooks, print_frame_bottom,
+    print_frame_top, print_spacer_rows, print_sticky_banner, redisplay_banner,
+    repaint_cycled_banner, save_snapshot, transition_animation, visible_width,
 };
 
 const FRAME_WIDTH_STEP: isize = 2;
+const MORE_MARKER: &str = "↓ więcej";
+const CONTENTS_TITLE: &str = "Spis treści";
+const CLOCK_TICK: Duration = Duration::from_secs(1);
+const WATCH_TICK: Duration = Duration::from_millis(200);
+const WIDTH_NOTICE: Duration = Duration::from_secs(1);
+const BUDGET_WARNING: &str = "\x1b[38;5;214m";
+const BUDGET_OVER: &str = "\x1b[38;5;196m";
+const TOTAL_BAR_CELLS: usize = 10;
+const PRESENTER_NOTES_ROWS: usize = 5;
+const TITLE_PUSH: &str = "\x1b[22;0t";
+const TITLE_POP: &str = "\x1b[23;0t";
+const VIM_SEQUENCE: Duration = Duration::from_millis(500);
+const VIM_KEYS: [(char, KeyCode); 5] = [
+    ('h', KeyCode::Left),
+    ('k', KeyCode::PageUp),
+    ('l', KeyCode::Right),
+    ('j', KeyCode::PageDown),
+    ('G', KeyCode::End),
+];
 
-pub(crate) fn run_presentation(config: &mut Config, segments: &[Segment]) -> io::Result<()> {
-    if segments.is_empty() {
+#[derive(Debug, Clone)]
+pub(crate) struct Slide {
+    segments: Vec<Segment>,
+    notes: Vec<String>,
+    source: PathBuf,
+    index_in_source: Option<usize>,
+    theme: Option<Result<SlideTheme, String>>,
+}
+
+/// Motyw wynika z segmentów i źródła, więc nie wchodzi do skrótu treści.
+impl Hash for Slide {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.segments.hash(state);
+        self.notes.hash(state);
+        self.source.hash(state);
+        self.index_in_source.hash(state);
+    }
+}
+
+impl Slide {
+    pub(crate) fn new(
+        segments: Vec<Segment>,
+        source: impl Into<PathBuf>,
+        index_in_source: Option<usize>,
+    ) -> Self {
+        let mut notes = Vec::new();
+        let mut segments: Vec<Segment> = segments
+            .into_iter()
+            .filter_map(|segment| match segment.kind {
+                SegmentKind::Notes(lines) => {
+                    notes.extend(lines);
+                    None
+                }
+                _ => Some(segment),
+            })
+            .collect();
+        renumber_lists(&mut segments);
+        let source = source.into();
+        let theme = segments
+            .iter()
+            .find_map(Segment::theme)
+            .map(|name| SlideTheme::resolve(name, &source));
+        Self {
+            segments,
+            notes,
+            source,
+            index_in_source,
+            theme,
+        }
+    }
+
+    pub(crate) fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Notatki prelegenta z linii po znaczniku `???` – nie są wyświetlane.
+    pub(crate) fn notes(&self) -> &[String] {
+        &self.notes
+    }
+
+    fn mentions_clock(&self) -> bool {
+        self.segments.iter().any(Segment::mentions_clock)
+    }
+
+    fn has_note_cues(&self) -> bool {
+        self.notes.iter().any(|note| notes::cue(note).is_some())
+    }
+
+    fn heading(&self) -> Option<String> {
+        self.segments.iter().find_map(Segment::heading)
+    }
+
+    /// Sekwencja rozpoczyna sekcję, gdy jej pierwszą treścią (poza odstępami
+    /// `@space`) jest nagłówek – do takich sekwencji skaczą `Tab` i `Shift+Tab`.
+    fn starts_section(&self) -> bool {
+        self.segments
+            .iter()
+            .find(|segment| !matches!(segment.kind, SegmentKind::Spacer(_)))
+            .is_some_and(|segment| matches!(segment.kind, SegmentKind::Heading(_)))
+    }
+
+    pub(crate) fn frame_title(&self) -> Option<&str> {
+        self.segments.iter().find_map(Segment::frame_title)
+    }
+
+    fn budget(&self) -> Option<Duration> {
+        self.segments.iter().find_map(Segment::budget)
+    }
+
+    pub(crate) fn theme(&self) -> Option<Result<&SlideTheme, &str>> {
+        self.theme
+            .as_ref()
+            .map(|theme| theme.as_ref().map_err(String::as_str))
+    }
+
+    pub(crate) fn source(&self) -> &Path {
+        &self.source
+    }
+
+    pub(crate) fn index_in_source(&self) -> Option<usize> {
+        self.index_in_source
+    }
+
+    fn source_label(&self) -> String {
+        let name = self
+            .source
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_else(|| self.source.to_string_lossy());
+        match self.index_in_source() {
+            Some(index) => format!("{} #{}", name, index),
+            None => name.into_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ViewState {
+    index: usize,
+    help_visible: bool,
+    pending_jump: String,
+    multi_source: bool,
+    closing: bool,
+    reload_error: Option<String>,
+    started: Option<Instant>,
+    terminal_rows: Option<u16>,
+    slide_started: Option<(usize, Instant)>,
+    scroll: Option<(usize, usize)>,
+    notes_scroll: Option<(usize, usize)>,
+    notes_error: Option<String>,
+    width_notice: Option<Instant>,
+    banner_cycle: Option<(usize, Instant)>,
+    dimmed: bool,
+    snapshot: Option<Result<PathBuf, String>>,
+}
+
+/// Kolejne punkty listy numerowanej dostają kolejne numery od numeru
+/// pierwszego z nich, więc wystarczy pisać `1.` przy każdym punkcie.
+fn renumber_lists(segments: &mut [Segment]) {
+    let mut next = None;
+    for segment in segments {
+        next = match &mut segment.kind {
+            SegmentKind::Numbered { number, .. } => {
+                *number = next.unwrap_or(*number);
+                Some(*number + 1)
+            }
+            _ => None,
+        };
+    }
+}
+
+/// Spis treści dla `--toc`: sekwencja na początku prezentacji z każdym
+/// nagłówkiem i numerem sekwencji, na której się znajduje (licząc sam spis).
+/// Prezentacja bez nagłówków pozostaje bez zmian.
+pub(crate) fn with_contents(slides: Vec<Slide>) -> Vec<Slide> {
+    let entries: Vec<Segment> = slides
+        .iter()
+        .enumerate()
+        .flat_map(|(index, slide)| {
+            slide
+                .segments
+                .iter()
+                .filter_map(move |segment| match &segment.kind {
+                    SegmentKind::Heading(title) => Some(Segment::new(SegmentKind::Contents {
+                        title: title.clone(),
+                        slide: index + 2,
+                    })),
+                    _ => None,
+                })
+        })
+        .collect();
+    let Some(first) = slides.first().filter(|_| !entries.is_empty()) else {
+        return slides;
+    };
+    let heading = Segment::new(SegmentKind::Heading(vec![Span::plain(CONTENTS_TITLE)]));
+    let contents = Slide::new(
+        std::iter::once(heading).chain(entries).collect(),
+        first.source.clone(),
+        None,
+    );
+    std::iter::once(contents).chain(slides).collect()
+}
+
+pub(crate) type DeckLoader<'a> = &'a dyn Fn() -> io::Result<Vec<Slide>>;
+
+/// Przeładowanie w trybie `--watch`: wczytanie talii od nowa oraz katalogi
+/// z wejścia, w których nowe i usunięte pliki zmieniają skład talii.
+pub(crate) struct DeckReload<'a> {
+    pub(crate) load: DeckLoader<'a>,
+    pub(crate) directories: Vec<PathBuf>,
+}
+
+pub(crate) trait EventSource {
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool>;
+    fn read(&mut self) -> io::Result<Event>;
+    fn rows(&self) -> Option<u16>;
+}
+
+struct TerminalEvents;
+
+impl EventSource for TerminalEvents {
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+        event::poll(timeout)
+    }
+
+    fn read(&mut self) -> io::Result<Event> {
+        event::read()
+    }
+
+    fn rows(&self) -> Option<u16> {
+        terminal::size().ok().map(|(_, rows)| rows)
+    }
+}
+
+pub(crate) fn run_presentation(
+    config: &mut Config,
+    slides: Vec<Slide>,
+    ending: Option<&Slide>,
+    reload: Option<&DeckReload>,
+) -> io::Result<()> {
+    if slides.is_empty() {
         return Ok(());
     }
 
     let mut stdout = io::stdout();
     stdout.flush()?;
-    let start_row = cursor::position().map(|(_, row)| row).unwrap_or(0);
-    let origin = (0, start_row);
+    let (origin, alt_screen) = if config.alt_screen() {
+        ((0, 0), Some(AltScreenGuard::new(&mut stdout)?))
+    } else {
+        match cursor::position() {
+            Ok((_, row)) => ((0, row), None),
+            Err(_) => match config.position_fallback() {
+                PositionFallback::Top => ((0, 0), None),
+                PositionFallback::AltScreen => ((0, 0), Some(AltScreenGuard::new(&mut stdout)?)),
+                PositionFallback::Scroll => {
+                    scroll_past_output(&mut stdout)?;
+                    ((0, 0), None)
+                }
+            },
+        }
+    };
 
+    let _title = if config.set_title() {
+        Some(TitleGuard::new(&mut stdout)?)
+    } else {
+        None
+    };
     let _raw_mode = RawModeGuard::new()?;
+    let _hidden_cursor = HiddenCursorGuard::new(&mut stdout)?;
+    let _panic_hook = PanicHookGuard::install(alt_screen.is_some());
+    present(
+        config,
+        slides,
+        ending,
+        reload,
+        origin,
+        &mut TerminalEvents,
+        &mut stdout,
+    )
+}
 
-    render(&mut stdout, origin, config, segments, 0, true)?;
-    let mut current_index = 0usize;
+fn present(
+    config: &mut Config,
+    mut slides: Vec<Slide>,
+    ending: Option<&Slide>,
+    reload: Option<&DeckReload>,
+    mut origin: (u16, u16),
+    events: &mut dyn EventSource,
+    stdout: &mut dyn Write,
+) -> io::Result<()> {
+    let mut ticking = needs_tick(config, &slides);
+    let mut deck_hash = content_hash(&slides);
+    let theme_watcher = match config.watched_theme_path() {
+        Some(path) => {
+            Some(FileWatcher::new(&[path], config.trace_events()).map_err(io::Error::other)?)
+        }
+        None => None,
+    };
+    let mut deck_watcher = match reload {
+        Some(reload) => Some(watch_deck(config, &slides, reload).map_err(io::Error::other)?),
+        None => None,
+    };
+    let mut banner_watcher = match (reload, config.banner_path()) {
+        (Some(_), Some(path)) => {
+            Some(FileWatcher::new(&[path], config.trace_events()).map_err(io::Error::other)?)
+        }
+        _ => None,
+    };
+    let poll_interval =
+        if theme_watcher.is_some() || deck_watcher.is_some() || banner_watcher.is_some() {
+            Some(WATCH_TICK)
+        } else if ticking || config.notes_out().is_some() {
+            Some(CLOCK_TICK)
+        } else {
+            None
+        };
+
+    let mut state = ViewState {
+        multi_source: is_multi_source(&slides),
+        banner_cycle: (config.banner_color_cycle()
+            && (config.sticky_banner().is_some() || config.printed_banner().is_some()))
+        .then(|| (0, Instant::now())),
+        started: Some(Instant::now()),
+        terminal_rows: events.rows(),
+        slide_started: Some((0, Instant::now())),
+        ..ViewState::default()
+    };
+    render(stdout, origin, config, &slides, ending, &state, true)?;
+    announce_slide(config, &slides, state.index);
+    let mut announced = state.index;
+    let mut shown_at = Instant::now();
+    let mut last_tick = Instant::now();
+    let mut repeat_guard = RepeatGuard::new(config.key_repeat_guard());
+    let mut key_map = KeyMap::default();
+    let mut last_activity = Instant::now();
+    let mut notes_out = config
+        .notes_out()
+        .map(|path| NotesOut::spawn(path.to_path_buf()));
+    let mut noted: Option<(usize, Instant)> = None;
 
     loop {
-        match event::read()? {
-            Event::Key(key) => match key.code {
-                KeyCode::Left => {
-                    if current_index > 0 {
-                        current_index -= 1;
-                        render(&mut stdout, origin, config, segments, current_index, true)?;
+        if state.index != announced {
+            announce_slide(config, &slides, state.index);
+            announced = state.index;
+            shown_at = Instant::now();
+            state.slide_started = Some((state.index, shown_at));
+            state.scroll = None;
+            state.notes_scroll = None;
+        }
+        if let Some(notes_out) = &mut notes_out {
+            if noted.is_none_or(|(index, at)| index != state.index || at.elapsed() >= CLOCK_TICK) {
+                let slide = &slides[state.index];
+                notes_out.write(notes::page(
+                    state.index + 1,
+                    slides.len(),
+                    state
+                        .started
+                        .map_or(Duration::ZERO, |started| started.elapsed()),
+                    slide.notes(),
+                ));
+                noted = Some((state.index, Instant::now()));
+            }
+            if let Some(warning) = notes_out.take_warning() {
+                state.notes_error = Some(warning);
+                render(stdout, origin, config, &slides, ending, &state, false)?;
+            }
+        }
+
+        let interval = match state.width_notice {
+            Some(_) => Some(poll_interval.map_or(WATCH_TICK, |tick| tick.min(WATCH_TICK))),
+            None => poll_interval,
+        };
+        let advance_remaining = match config.replay_dwell(state.index) {
+            Some(dwell) => Some(dwell.saturating_sub(shown_at.elapsed())),
+            None => config
+                .auto_advance()
+                .map(|every| every.saturating_sub(shown_at.elapsed().min(last_activity.elapsed()))),
+        }
+        .filter(|_| {
+            !state.closing
+                && !config.paused(state.index)
+                && (state.index + 1 < slides.len() || config.loop_playback())
+        });
+        let cycle_remaining = state
+            .banner_cycle
+            .map(|(_, stepped)| BANNER_CYCLE_STEP.saturating_sub(stepped.elapsed()));
+        let idle_remaining = config
+            .idle_dim()
+            .filter(|_| !state.dimmed)
+            .map(|idle| idle.saturating_sub(last_activity.elapsed()));
+        let interval = sooner(
+            sooner(interval, advance_remaining),
+            sooner(cycle_remaining, idle_remaining),
+        );
+        if let Some(interval) = interval
+            && !events.poll(interval)?
+        {
+            let theme_changed = theme_watcher
+                .as_ref()
+                .is_some_and(|watcher| watcher.changed())
+                && config.reload_theme().is_ok();
+            let mut deck_changed = deck_watcher
+                .as_ref()
+                .is_some_and(|watcher| watcher.changed());
+            let banner_changed = banner_watcher
+                .as_ref()
+                .is_some_and(|watcher| watcher.changed());
+            let sticky_changed = banner_changed
+                && config.sticky_banner().is_some()
+                && config.reload_banner().is_ok();
+            if let Some(reload) = reload
+                && deck_changed
+            {
+                match (reload.load)() {
+                    Ok(deck)
+                        if content_hash(&deck) == deck_hash
+                            && state.reload_error.is_none()
+                            && !config.force_reload() =>
+                    {
+                        deck_changed = false;
+                        print_unchanged_notice(stdout, config)?;
+                    }
+                    Ok(deck) if !deck.is_empty() => {
+                        deck_hash = content_hash(&deck);
+                        state.index = state.index.min(deck.len() - 1);
+                        state.multi_source = is_multi_source(&deck);
+                        state.reload_error = None;
+                        ticking = needs_tick(config, &deck);
+                        slides = deck;
+                        if config.watch_once() {
+                            deck_watcher = None;
+                            banner_watcher = None;
+                        } else if let Ok(watcher) = watch_deck(config, &slides, reload) {
+                            deck_watcher = Some(watcher);
+                        }
+                    }
+                    Ok(_) => state.reload_error = Some("brak treści".to_string()),
+                    Err(error) => state.reload_error = Some(error.to_string()),
+                }
+            }
+            if banner_changed
+                && config.sticky_banner().is_none()
+                && !config.alt_screen()
+                && let Some(row) = redisplay_banner(config)?
+            {
+                // Nowy baner nie zmienia już kolorów.
+                config.set_printed_banner(None);
+                state.banner_cycle = None;
+                origin.1 = row;
+                render(stdout, origin, config, &slides, ending, &state, true)?;
+                last_tick = Instant::now();
+                continue;
+            }
+            let clock_due = ticking && last_tick.elapsed() >= CLOCK_TICK;
+            let notice_expired = state
+                .width_notice
+                .is_some_and(|shown| shown.elapsed() >= WIDTH_NOTICE);
+            if notice_expired {
+                state.width_notice = None;
+            }
+            if let Some((phase, stepped)) = state.banner_cycle
+                && stepped.elapsed() >= BANNER_CYCLE_STEP
+            {
+                state.banner_cycle = Some((phase + 1, Instant::now()));
+                repaint_cycled_banner(stdout, config, origin.1, banner_cycle_slot(phase + 1))?;
+            }
+            let idle_due = idle_remaining.is_some_and(|remaining| remaining <= interval);
+            if idle_due {
+                state.dimmed = true;
+            }
+            if advance_remaining.is_some_and(|remaining| remaining <= interval) {
+                state.index = (state.index + 1) % slides.len();
+                state.dimmed = false;
+                last_activity = Instant::now();
+                render(stdout, origin, config, &slides, ending, &state, true)?;
+                last_tick = Instant::now();
+                continue;
+            }
+            if theme_changed
+                || deck_changed
+                || sticky_changed
+                || clock_due
+                || notice_expired
+                || idle_due
+            {
+                render(stdout, origin, config, &slides, ending, &state, false)?;
+                last_tick = Instant::now();
+            }
+            continue;
+        }
+
+        let event = events.read()?;
+        if let Event::Key(_) = event
+            && state.banner_cycle.take().is_some()
+        {
+            repaint_cycled_banner(stdout, config, origin.1, ColorSlot::Glow)?;
+        }
+        if let Event::Key(_) = event {
+            last_activity = Instant::now();
+            state.snapshot = None;
+            state.notes_error = None;
+            if std::mem::take(&mut state.dimmed) {
+                render(stdout, origin, config, &slides, ending, &state, false)?;
+                continue;
+            }
+        }
+        let window = match event {
+            Event::Key(_) if !state.closing => {
+                scroll_window(origin, config, &slides, ending, &state)?
+            }
+            _ => None,
+        };
+        let hidden_below = window
+            .as_ref()
+            .map(|window| window.end)
+            .filter(|end| *end < slides[state.index].segments().len());
+        let hidden_above = window
+            .filter(|window| window.start > 0)
+            .map(|window| window.start.saturating_sub(window.len()));
+        match event {
+            Event::Key(_) if state.closing => break,
+            Event::Key(key) => match key_map.translate(key.code) {
+                KeyCode::Char(digit) if digit.is_ascii_digit() => {
+                    state.pending_jump.push(digit);
+                    render(stdout, origin, config, &slides, ending, &state, false)?;
+                }
+                KeyCode::Backspace if !state.pending_jump.is_empty() => {
+                    state.pending_jump.pop();
+                    render(stdout, origin, config, &slides, ending, &state, false)?;
+                }
+                KeyCode::Esc if !state.pending_jump.is_empty() => {
+                    state.pending_jump.clear();
+                    render(stdout, origin, config, &slides, ending, &state, false)?;
+                }
+                KeyCode::Enter if !state.pending_jump.is_empty() => {
+                    let number = std::mem::take(&mut state.pending_jump)
+                        .parse::<usize>()
+                        .unwrap_or(usize::MAX);
+                    state.index = number.clamp(1, slides.len()) - 1;
+                    render(stdout, origin, config, &slides, ending, &state, true)?;
+                }
+                KeyCode::PageDown if hidden_below.is_some() => {
+                    state.scroll = hidden_below.map(|first| (state.index, first));
+                    render(stdout, origin, config, &slides, ending, &state, false)?;
+                }
+                KeyCode::PageUp if hidden_above.is_some() => {
+                    state.scroll = hidden_above.map(|first| (state.index, first));
+                    render(stdout, origin, config, &slides, ending, &state, false)?;
+                }
+                KeyCode::Up | KeyCode::Down if config.presenter_view() && !state.closing => {
+                    let last = slides[state.index]
+                        .notes()
+                        .len()
+                        .saturating_sub(PRESENTER_NOTES_ROWS);
+                    let first = notes_offset(&state);
+                    let moved = if key.code == KeyCode::Up {
+                        first.saturating_sub(1)
+                    } else {
+                        (first + 1).min(last)
+                    };
+                    if moved != first {
+                        state.notes_scroll = Some((state.index, moved));
+                        render(stdout, origin, config, &slides, ending, &state, false)?;
                     }
                 }
-                KeyCode::Right | KeyCode::Enter => {
-                    if current_index + 1 < segments.len() {
-                        current_index += 1;
-                        render(&mut stdout, origin, config, segments, current_index, true)?;
+                KeyCode::Left | KeyCode::PageUp
+                    if (state.index > 0 || config.loop_playback())
+                        && repeat_guard.allows(false) =>
+                {
+                    state.index = state.index.checked_sub(1).unwrap_or(slides.len() - 1);
+                    render(stdout, origin, config, &slides, ending, &state, true)?;
+                }
+                KeyCode::Home if state.index > 0 => {
+                    state.index = 0;
+                    render(stdout, origin, config, &slides, ending, &state, true)?;
+                }
+                KeyCode::End if state.index + 1 < slides.len() => {
+                    state.index = slides.len() - 1;
+                    render(stdout, origin, config, &slides, ending, &state, true)?;
+                }
+                KeyCode::Tab if state.index + 1 < slides.len() => {
+                    state.index = (state.index + 1..slides.len())
+                        .find(|&index| slides[index].starts_section())
+                        .unwrap_or(slides.len() - 1);
+                    render(stdout, origin, config, &slides, ending, &state, true)?;
+                }
+                KeyCode::BackTab if state.index > 0 => {
+                    state.index = (0..state.index)
+                        .rev()
+                        .find(|&index| slides[index].starts_section())
+                        .unwrap_or(0);
+                    render(stdout, origin, config, &slides, ending, &state, true)?;
+                }
+                KeyCode::Right | KeyCode::Enter | KeyCode::PageDown
+                    if (key.code != KeyCode::Enter
+                        || config.enter_action() == EnterAction::Next)
+                        && repeat_guard.allows(true) =>
+                {
+                    if state.index + 1 < slides.len() {
+                        state.index += 1;
+                        render(stdout, origin, config, &slides, ending, &state, true)?;
+                    } else if config.loop_playback() {
+                        state.index = 0;
+                        render(stdout, origin, config, &slides, ending, &state, true)?;
+                    } else if ending.is_some() {
+                        state.closing = true;
+                        render(stdout, origin, config, &slides, ending, &state, true)?;
                     } else {
                         break;
                     }
                 }
+                KeyCode::Enter if config.enter_action() == EnterAction::Reveal => {
+                    render(stdout, origin, config, &slides, ending, &state, false)?;
+                }
                 KeyCode::Char('q') | KeyCode::Char('Q') => break,
-                KeyCode::Char('+') | KeyCode::Char('=') => {
-                    if config.adjust_frame_width(FRAME_WIDTH_STEP) {
-                        render(&mut stdout, origin, config, segments, current_index, false)?;
-                    }
+                KeyCode::Char('+') | KeyCode::Char('=')
+                    if config.adjust_frame_width(FRAME_WIDTH_STEP) =>
+                {
+                    state.width_notice = Some(Instant::now());
+                    render(stdout, origin, config, &slides, ending, &state, false)?;
+                }
+                KeyCode::Char('-') | KeyCode::Char('_')
+                    if config.adjust_frame_width(-FRAME_WIDTH_STEP) =>
+                {
+                    state.width_notice = Some(Instant::now());
+                    render(stdout, origin, config, &slides, ending, &state, false)?;
+                }
+                KeyCode::Char('t') if config.cycle_theme(true) => {
+                    render(stdout, origin, config, &slides, ending, &state, false)?;
                 }
-                KeyCode::Char('-') | KeyCode::Char('_') => {
-                    if config.adjust_frame_width(-FRAME_WIDTH_STEP) {
-                        render(&mut stdout, origin, config, segments, current_index, false)?;
+                KeyCode::Char('T') if config.cycle_theme(false) => {
+                    render(stdout, origin, config, &slides, ending, &state, false)?;
+                }
+                KeyCode::Char('p') | KeyCode::Char('P') => {
+                    if let Some(directory) = config.snapshot_dir() {
+                        let saved = save_snapshot(config, &slides, state.index, directory);
+                        state.snapshot = Some(saved.map_err(|error| error.to_string()));
+                        render(stdout, origin, config, &slides, ending, &state, false)?;
                     }
                 }
+                KeyCode::Char('?') => {
+                    state.help_visible = !state.help_visible;
+                    render(stdout, origin, config, &slides, ending, &state, false)?;
+                }
                 KeyCode::Esc => break,
                 _ => {}
             },
-            Event::Resize(_, _) => {
-                render(&mut stdout, origin, config, segments, current_index, false)?;
+            Event::Resize(columns, rows) => {
+                config.fit_to_terminal(columns);
+                state.terminal_rows = Some(rows);
+                render(stdout, origin, config, &slides, ending, &state, false)?;
             }
             _ => {}
         }
@@ -67,61 +683,778 @@ pub(crate) fn run_presentation(config: &mut Config, segments: &[Segment]) -> io:
     Ok(())
 }
 
+fn sooner(left: Option<Duration>, right: Option<Duration>) -> Option<Duration> {
+    match (left, right) {
+        (Some(left), Some(right)) => Some(left.min(right)),
+        (left, right) => left.or(right),
+    }
+}
+
+fn mentions_clock(slides: &[Slide]) -> bool {
+    slides.iter().any(Slide::mentions_clock)
+}
+
+/// Zegar oraz – w widoku prezentera – odliczanie `@budget`, `--total-budget`
+/// i znaczniki czasu w notatkach wymagają przerysowania co sekundę.
+fn needs_tick(config: &Config, slides: &[Slide]) -> bool {
+    mentions_clock(slides)
+        || config.presenter_view()
+            && (config.total_budget().is_some()
+                || slides
+                    .iter()
+                    .any(|slide| slide.budget().is_some() || slide.has_note_cues()))
+}
+
+fn content_hash(slides: &[Slide]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    slides.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn is_multi_source(slides: &[Slide]) -> bool {
+    slides
+        .windows(2)
+        .any(|pair| pair[0].source() != pair[1].source())
+}
+
+fn deck_sources(slides: &[Slide]) -> Vec<&Path> {
+    let mut sources: Vec<&Path> = Vec::new();
+    for slide in slides {
+        if !sources.contains(&slide.source()) {
+            sources.push(slide.source());
+        }
+    }
+    sources
+}
+
+/// Obserwator plików talii i katalogów z wejścia; po każdym przeładowaniu
+/// tworzony od nowa, bo skład talii mógł się zmienić.
+fn watch_deck(
+    config: &Config,
+    slides: &[Slide],
+    reload: &DeckReload,
+) -> notify::Result<FileWatcher> {
+    let mut paths = deck_sources(slides);
+    paths.extend(reload.directories.iter().map(PathBuf::as_path));
+    FileWatcher::new(&paths, config.trace_events())
+}
+
+fn announce_slide(config: &Config, slides: &[Slide], index: usize) {
+    let Some(command) = config.on_slide() else {
+        return;
+    };
+
+    let heading = slides[..=index]
+        .iter()
+        .rev()
+        .find_map(Slide::heading)
+        .unwrap_or_else(|| config.presentation_title().to_string());
+    hooks::on_slide(command, index, &heading, slides[index].notes());
+}
+
+/// Jedno rysowanie sekwencji; `segments` zawęża treść do okna przewijania,
+/// gdy sekwencja nie mieści się w terminalu.
+#[derive(Debug, Clone, Default)]
+struct Pass {
+    animate: bool,
+    segments: Option<Range<usize>>,
+}
+
+/// Z `--vcenter` wysokość sekwencji mierzy próbne rysowanie bez animacji,
+/// a ramka zaczyna się niżej o połowę wolnych wierszy terminala.
 fn render(
-    stdout: &mut Stdout,
+    stdout: &mut dyn Write,
     origin: (u16, u16),
     config: &Config,
-    segments: &[Segment],
-    index: usize,
+    slides: &[Slide],
+    ending: Option<&Slide>,
+    state: &ViewState,
     animate: bool,
 ) -> io::Result<()> {
+    let segments = scroll_window(origin, config, slides, ending, state)?;
+    let pass = |animate| Pass {
+        animate,
+        segments: segments.clone(),
+    };
+    let padding = match state.terminal_rows.filter(|_| config.vcenter()) {
+        Some(rows) => {
+            let height = drawn_rows(origin, config, slides, ending, state, pass(false))?;
+            vertical_padding(rows.saturating_sub(origin.1), height)
+        }
+        None => 0,
+    };
+    if padding > 0 {
+        stdout.execute(cursor::MoveTo(origin.0, origin.1))?;
+        stdout.execute(Clear(ClearType::FromCursorDown))?;
+    }
+    let origin = (origin.0, origin.1.saturating_add(padding));
+    draw(stdout, origin, config, slides, ending, state, pass(animate))
+}
+
+/// Segmenty bieżącej sekwencji mieszczące się w terminalu od pierwszego
+/// przewiniętego; `None`, gdy sekwencja mieści się w całości.
+fn scroll_window(
+    origin: (u16, u16),
+    config: &Config,
+    slides: &[Slide],
+    ending: Option<&Slide>,
+    state: &ViewState,
+) -> io::Result<Option<Range<usize>>> {
+    let total = slides[state.index].segments().len();
+    let Some(rows) = state.terminal_rows.filter(|_| !state.closing && total > 0) else {
+        return Ok(None);
+    };
+    let available = usize::from(rows.saturating_sub(origin.1));
+    let first = match state.scroll {
+        Some((index, first)) if index == state.index => first.min(total - 1),
+        _ => 0,
+    };
+    if first == 0 && drawn_rows(origin, config, slides, ending, state, Pass::default())? < available
+    {
+        return Ok(None);
+    }
+    let mut end = first + 1;
+    while end < total {
+        let pass = Pass {
+            animate: false,
+            segments: Some(first..end + 1),
+        };
+        if drawn_rows(origin, config, slides, ending, state, pass)? >= available {
+            break;
+        }
+        end += 1;
+    }
+    Ok(Some(first..end))
+}
+
+fn drawn_rows(
+    origin: (u16, u16),
+    config: &Config,
+    slides: &[Slide],
+    ending: Option<&Slide>,
+    state: &ViewState,
+    pass: Pass,
+) -> io::Result<usize> {
+    let mut probe = Vec::new();
+    draw(&mut probe, origin, config, slides, ending, state, pass)?;
+    Ok(probe.iter().filter(|byte| **byte == b'\n').count())
+}
+
+fn vertical_padding(rows: u16, height: usize) -> u16 {
+    let free = usize::from(rows).saturating_sub(height);
+    (free / 2) as u16
+}
+
+fn draw(
+    stdout: &mut dyn Write,
+    origin: (u16, u16),
+    config: &Config,
+    slides: &[Slide],
+    ending: Option<&Slide>,
+    state: &ViewState,
+    pass: Pass,
+) -> io::Result<()> {
+    let slide = &slides[state.index];
+    let closing = ending.filter(|_| state.closing);
+    let shown = closing.unwrap_or(slide);
+    let themed = shown
+        .theme()
+        .map(|theme| theme.map(|theme| config.themed(theme)));
+    let (config, theme_error) = match &themed {
+        Some(Ok(themed)) => (themed, None),
+        Some(Err(error)) => (config, Some(*error)),
+        None => (config, None),
+    };
+    let dimmed = state.dimmed.then(|| config.dimmed());
+    let config = dimmed.as_ref().unwrap_or(config);
+
     stdout.execute(cursor::MoveTo(origin.0, origin.1))?;
     stdout.execute(Clear(ClearType::FromCursorDown))?;
+    if config.set_title() {
+        let title = slide
+            .heading()
+            .unwrap_or_else(|| config.presentation_title().to_string());
+        let title: String = title.chars().filter(|ch| !ch.is_control()).collect();
+        write!(stdout, "\x1b]0;{}\x07", title)?;
+    }
+    let banner_slot = state
+        .banner_cycle
+        .map_or(ColorSlot::Glow, |(phase, _)| banner_cycle_slot(phase));
+    print_sticky_banner(stdout, config, banner_slot)?;
 
-    if animate && config.animations_enabled() {
-        transition_animation(config)?;
-        println!();
+    if pass.animate && config.animations_enabled() {
+        transition_animation(stdout, config)?;
+        writeln!(stdout)?;
     }
 
-    print_frame_top(config);
-    animate_line(config, index, &segments[index], animate)?;
-    print_frame_bottom(config);
-    println!();
-    print_instructions(config, index, segments.len());
+    let frame_title = shown.frame_title();
+    let corner = match state.width_notice {
+        Some(_) => Some(format!("↔ {}", config.frame_width())),
+        None => config.corner_number(state.index, slides.len()),
+    };
+    print_frame_top(stdout, config, frame_title, corner.as_deref())?;
+    let segments = shown.segments();
+    let window = pass.segments.unwrap_or(0..segments.len());
+    let more = (window.end < segments.len()).then_some(MORE_MARKER);
+    for (position, segment) in segments[window].iter().enumerate() {
+        if position > 0 {
+            print_spacer_rows(stdout, config, config.line_spacing())?;
+        }
+        if let SegmentKind::Heading(_) = segment.kind() {
+            print_spacer_rows(stdout, config, config.heading_spacing())?;
+        }
+        animate_line(stdout, config, state.index, segment, pass.animate)?;
+    }
+    print_frame_bottom(stdout, config, more)?;
+    if config.progress_bar() {
+        print_progress_bar(stdout, config, state.index, slides.len())?;
+    }
+    writeln!(stdout)?;
+    let source = state.multi_source.then(|| slide.source_label());
+    print_instructions(
+        stdout,
+        config,
+        state,
+        slides.len(),
+        source,
+        themed
+            .as_ref()
+            .and_then(|themed| themed.as_ref().ok())
+            .map(Config::theme_label),
+    )?;
+    if mentions_clock(slides) {
+        print_clock(stdout, config)?;
+    }
+    let presenter = config.presenter_view();
+    if let Some(budget) = slide.budget().filter(|_| presenter && closing.is_none()) {
+        let elapsed = match state.slide_started {
+            Some((index, at)) if index == state.index => at.elapsed(),
+            _ => Duration::ZERO,
+        };
+        print_budget(stdout, config, budget, elapsed)?;
+    }
+    if let (true, Some(budget), Some(started)) = (presenter, config.total_budget(), state.started) {
+        print_total_budget(stdout, config, budget, started.elapsed())?;
+    }
+    if presenter && closing.is_none() {
+        let elapsed = state
+            .started
+            .map_or(Duration::ZERO, |started| started.elapsed());
+        print_presenter_notes(stdout, config, slide.notes(), notes_offset(state), elapsed)?;
+    }
+    if let Some(error) = theme_error {
+        writeln!(
+            stdout,
+            "{}⚠ {} – użyto motywu prezentacji{}",
+            config.color_dim(),
+            error,
+            config.style(RESET)
+        )?;
+    }
+    match &state.snapshot {
+        Some(Ok(path)) => writeln!(
+            stdout,
+            "{}SNAP ::{} {}{}{}",
+            config.color_dim(),
+            config.style(RESET),
+            config.color_glow(),
+            path.display(),
+            config.style(RESET)
+        )?,
+        Some(Err(error)) => writeln!(
+            stdout,
+            "{}⚠ Nie udało się zapisać migawki: {}{}",
+            config.color_dim(),
+            error,
+            config.style(RESET)
+        )?,
+        None => {}
+    }
+    if let Some(error) = &state.notes_error {
+        writeln!(
+            stdout,
+            "{}⚠ Nie udało się zapisać notatek: {}{}",
+            config.color_dim(),
+            error,
+            config.style(RESET)
+        )?;
+    }
+    if let Some(error) = &state.reload_error {
+        writeln!(
+            stdout,
+            "{}⚠ Błąd przeładowania (wyświetlana ostatnia poprawna wersja): {}{}",
+            config.color_dim(),
+            error,
+            config.style(RESET)
+        )?;
+    }
     stdout.flush()?;
 
     Ok(())
 }
 
-fn print_instructions(config: &Config, index: usize, total: usize) {
-    println!(
-        "{}CTRL ::{} {}←/→{} lub Enter sekwencje  {}+/-{} szerokość  {}Q/Esc{} wyjście  {}SEQ ::{} {}{:03}/{:03}{}  {}FRAME ::{} {}{}{}",
+fn print_instructions(
+    out: &mut dyn Write,
+    config: &Config,
+    state: &ViewState,
+    total: usize,
+    source: Option<String>,
+    theme: Option<&str>,
+) -> io::Result<()> {
+    let index = state.index;
+    let jump = (!state.pending_jump.is_empty()).then(|| {
+        format!(
+            "{}IDŹ DO ::{} {}{}_{}",
+            config.color_dim(),
+            config.style(RESET),
+            config.color_glow(),
+            state.pending_jump,
+            config.style(RESET)
+        )
+    });
+    let mut fields: Vec<String> = config
+        .footer_fields()
+        .iter()
+        .filter_map(|field| footer_field(config, *field, index, total, source.as_deref()))
+        .collect();
+    if let Some(theme) = theme {
+        fields.push(format!(
+            "{}THEME ::{} {}{}{}",
+            config.color_dim(),
+            config.style(RESET),
+            config.color_glow(),
+            config.upcase(theme),
+            config.style(RESET)
+        ));
+    }
+    if config.loop_playback() {
+        fields.push(format!(
+            "{}LOOP ::{} {}↻{}",
+            config.color_dim(),
+            config.style(RESET),
+            config.color_glow(),
+            config.style(RESET)
+        ));
+    }
+    fields.extend(jump.clone());
+    let full = fields.join("  ");
+
+    let fits = visible_width(&full) <= config.frame_width();
+    if state.help_visible || (fits && !config.compact_footer()) {
+        return writeln!(out, "{}", full);
+    }
+
+    writeln!(
+        out,
+        "{}SEQ ::{} {}{}/{}{}  {}?{} pomoc{}",
         config.color_dim(),
-        RESET,
+        config.style(RESET),
+        config.color_accent(),
+        index + 1,
+        total,
+        config.style(RESET),
         config.color_glow(),
-        RESET,
+        config.style(RESET),
+        jump.map(|jump| format!("  {}", jump)).unwrap_or_default()
+    )
+}
+
+fn footer_field(
+    config: &Config,
+    field: FooterField,
+    index: usize,
+    total: usize,
+    source: Option<&str>,
+) -> Option<String> {
+    let field = match field {
+        FooterField::Ctrl => format!(
+            "{}CTRL ::{} {}←/→{}{} sekwencje  {}+/-{} szerokość  {}Q/Esc{} wyjście",
+            config.color_dim(),
+            config.style(RESET),
+            config.color_glow(),
+            config.style(RESET),
+            if config.enter_action() == EnterAction::Next {
+                " lub Enter"
+            } else {
+                ""
+            },
+            config.color_glow(),
+            config.style(RESET),
+            config.color_glow(),
+            config.style(RESET)
+        ),
+        FooterField::Seq => format!(
+            "{}SEQ ::{} {}{:03}/{:03}{}",
+            config.color_dim(),
+            config.style(RESET),
+            config.color_accent(),
+            index + 1,
+            total,
+            config.style(RESET)
+        ),
+        FooterField::Frame => format!(
+            "{}FRAME ::{} {}{}{}",
+            config.color_dim(),
+            config.style(RESET),
+            config.color_accent(),
+            config.frame_width(),
+            config.style(RESET)
+        ),
+        FooterField::Src => format!(
+            "{}SRC ::{} {}{}{}",
+            config.color_dim(),
+            config.style(RESET),
+            config.color_accent(),
+            source?,
+            config.style(RESET)
+        ),
+    };
+    Some(field)
+}
+
+fn print_unchanged_notice(stdout: &mut dyn Write, config: &Config) -> io::Result<()> {
+    write!(
+        stdout,
+        "\r{}· brak zmian w treści – pominięto przeładowanie{}",
+        config.color_dim(),
+        config.style(RESET)
+    )?;
+    stdout.execute(Clear(ClearType::UntilNewLine))?;
+    Ok(())
+}
+
+fn print_clock(out: &mut dyn Write, config: &Config) -> io::Result<()> {
+    writeln!(
+        out,
+        "{}CLOCK ::{} {}{}{}",
+        config.color_dim(),
+        config.style(RESET),
         config.color_glow(),
-        RESET,
+        clock::now(config.time_format()),
+        config.style(RESET)
+    )
+}
+
+/// Pasek na szerokość ramki: wypełniona część w kolorze `glow` odpowiada
+/// pozycji w prezentacji, a etykieta z procentem zajmuje ostatnie kolumny.
+fn print_progress_bar(
+    out: &mut dyn Write,
+    config: &Config,
+    index: usize,
+    total: usize,
+) -> io::Result<()> {
+    let percent = (index + 1) * 100 / total.max(1);
+    let label = format!(" {:>3}%", percent);
+    let cells = config.frame_width().saturating_sub(label.len());
+    let filled = cells * (index + 1) / total.max(1);
+    writeln!(
+        out,
+        "{}{}{}{}{}{}",
         config.color_glow(),
-        RESET,
+        "█".repeat(filled),
         config.color_dim(),
-        RESET,
-        config.color_accent(),
-        index + 1,
-        total,
-        RESET,
+        "░".repeat(cells - filled),
+        label,
+        config.style(RESET)
+    )
+}
+
+fn print_budget(
+    out: &mut dyn Write,
+    config: &Config,
+    budget: Duration,
+    elapsed: Duration,
+) -> io::Result<()> {
+    let pace = pace(elapsed, budget);
+    let value = match pace {
+        Pace::Over => "OVERTIME".to_string(),
+        _ => minutes_seconds(budget.saturating_sub(elapsed).as_secs_f64().ceil() as u64),
+    };
+    writeln!(
+        out,
+        "{}BUDGET ::{} {}{}{}",
         config.color_dim(),
-        RESET,
-        config.color_accent(),
-        config.frame_width(),
-        RESET
-    );
+        config.style(RESET),
+        pace_color(config, pace),
+        value,
+        config.style(RESET)
+    )
+}
+
+fn print_total_budget(
+    out: &mut dyn Write,
+    config: &Config,
+    budget: Duration,
+    elapsed: Duration,
+) -> io::Result<()> {
+    let filled = ((elapsed.as_secs_f64() / budget.as_secs_f64()) * TOTAL_BAR_CELLS as f64)
+        .floor()
+        .min(TOTAL_BAR_CELLS as f64) as usize;
+    writeln!(
+        out,
+        "{}TOTAL ::{} {}{}{}{}{} {}/{}",
+        config.color_dim(),
+        config.style(RESET),
+        pace_color(config, pace(elapsed, budget)),
+        "█".repeat(filled),
+        config.color_dim(),
+        "░".repeat(TOTAL_BAR_CELLS - filled),
+        config.style(RESET),
+        minutes_seconds(elapsed.as_secs()),
+        minutes_seconds(budget.as_secs())
+    )
+}
+
+/// Przesunięcie notatek bieżącej sekwencji; po zmianie sekwencji wraca do
+/// początku.
+fn notes_offset(state: &ViewState) -> usize {
+    match state.notes_scroll {
+        Some((index, first)) if index == state.index => first,
+        _ => 0,
+    }
+}
+
+/// Notatki bieżącej sekwencji w widoku prezentera – najwyżej
+/// `PRESENTER_NOTES_ROWS` od notatki `first`, przewijane `↑`/`↓`. Znaczniki
+/// `[m:ss]` są przygaszone, a notatka, przy której prelegent powinien teraz
+/// być, świeci kolorem `glow`.
+fn print_presenter_notes(
+    out: &mut dyn Write,
+    config: &Config,
+    notes: &[String],
+    first: usize,
+    elapsed: Duration,
+) -> io::Result<()> {
+    if notes.is_empty() {
+        return Ok(());
+    }
+    let first = first.min(notes.len().saturating_sub(PRESENTER_NOTES_ROWS));
+    let position = if notes.len() > PRESENTER_NOTES_ROWS {
+        format!(" ({}/{})", first + 1, notes.len())
+    } else {
+        String::new()
+    };
+    writeln!(
+        out,
+        "{}NOTES{} ::{}",
+        config.color_dim(),
+        position,
+        config.style(RESET)
+    )?;
+    let current = notes::current_cue(notes, elapsed);
+    for (index, note) in notes
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(PRESENTER_NOTES_ROWS)
+    {
+        let (stamp, text) = match notes::cue(note) {
+            Some((at, text)) => (
+                format!(
+                    "{}[{}]{} ",
+                    config.color_dim(),
+                    minutes_seconds(at.as_secs()),
+                    config.style(RESET)
+                ),
+                text,
+            ),
+            None => (String::new(), note.trim()),
+        };
+        let color = if current == Some(index) {
+            format!("{}{}", config.color_glow(), config.style(BOLD))
+        } else {
+            config.color_accent().to_string()
+        };
+        writeln!(out, "  {}{}{}{}", stamp, color, text, config.style(RESET))?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pace {
+    Ok,
+    Warning,
+    Over,
+}
+
+/// Ostatnia ćwierć przydzielonego czasu to ostrzeżenie, a jego wyczerpanie –
+/// przekroczenie.
+fn pace(elapsed: Duration, budget: Duration) -> Pace {
+    if elapsed >= budget {
+        Pace::Over
+    } else if budget - elapsed <= budget / 4 {
+        Pace::Warning
+    } else {
+        Pace::Ok
+    }
+}
+
+fn pace_color(config: &Config, pace: Pace) -> &str {
+    match pace {
+        Pace::Ok => config.color_glow(),
+        Pace::Warning => config.style(BUDGET_WARNING),
+        Pace::Over => config.style(BUDGET_OVER),
+    }
+}
+
+fn minutes_seconds(seconds: u64) -> String {
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn scroll_past_output(stdout: &mut Stdout) -> io::Result<()> {
+    let rows = terminal::size().map(|(_, rows)| rows).unwrap_or(24);
+    write!(stdout, "{}", "\n".repeat(usize::from(rows)))?;
+    stdout.flush()
+}
+
+struct RepeatGuard {
+    window: Duration,
+    last_step: Option<(bool, Instant)>,
+}
+
+impl RepeatGuard {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            last_step: None,
+        }
+    }
+
+    fn allows(&mut self, forward: bool) -> bool {
+        let now = Instant::now();
+        if let Some((direction, at)) = self.last_step
+            && direction == forward
+            && now.duration_since(at) < self.window
+        {
+            return false;
+        }
+        self.last_step = Some((forward, now));
+        true
+    }
+}
+
+/// Zamienia klawisze w stylu Vima na ich odpowiedniki z `VIM_KEYS`; `gg`
+/// wciśnięte w odstępie `VIM_SEQUENCE` działa jak `Home`.
+#[derive(Default)]
+struct KeyMap {
+    pending_g: Option<Instant>,
+}
+
+impl KeyMap {
+    fn translate(&mut self, code: KeyCode) -> KeyCode {
+        let pending_g = self.pending_g.take();
+        match code {
+            KeyCode::Char('g') if pending_g.is_some_and(|at| at.elapsed() < VIM_SEQUENCE) => {
+                KeyCode::Home
+            }
+            KeyCode::Char('g') => {
+                self.pending_g = Some(Instant::now());
+                KeyCode::Null
+            }
+            KeyCode::Char(ch) => VIM_KEYS
+                .iter()
+                .find(|(key, _)| *key == ch)
+                .map_or(code, |(_, mapped)| *mapped),
+            _ => code,
+        }
+    }
+}
+
+/// Odkłada tytuł okna na stos terminala (xterm) i przywraca go na końcu.
+struct TitleGuard;
+
+impl TitleGuard {
+    fn new(stdout: &mut Stdout) -> io::Result<Self> {
+        write!(stdout, "{}", TITLE_PUSH)?;
+        stdout.flush()?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TitleGuard {
+    fn drop(&mut self) {
+        let mut stdout = io::stdout();
+        let _ = write!(stdout, "{}", TITLE_POP);
+        let _ = stdout.flush();
+    }
+}
+
+struct HiddenCursorGuard;
+
+impl HiddenCursorGuard {
+    fn new(stdout: &mut Stdout) -> io::Result<Self> {
+        stdout.execute(cursor::Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for HiddenCursorGuard {
+    fn drop(&mut self) {
+        let _ = io::stdout().execute(cursor::Show);
+    }
+}
+
+struct AltScreenGuard;
+
+impl AltScreenGuard {
+    fn new(stdout: &mut Stdout) -> io::Result<Self> {
+        stdout.execute(terminal::EnterAlternateScreen)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for AltScreenGuard {
+    fn drop(&mut self) {
+        let _ = io::stdout().execute(terminal::LeaveAlternateScreen);
+    }
+}
+
+type PanicHook = Arc<dyn Fn(&PanicHookInfo<'_>) + Send + Sync>;
+
+/// Na czas prezentacji poprzedza komunikat paniki przywróceniem terminala –
+/// domyślny hook pisze przed zwinięciem stosu, więc bez tego komunikat
+/// trafiłby do trybu surowego lub znikłby razem z alternatywnym ekranem.
+struct PanicHookGuard {
+    previous: PanicHook,
 }
 
-struct RawModeGuard;
+impl PanicHookGuard {
+    fn install(alt_screen: bool) -> Self {
+        let previous: PanicHook = Arc::from(panic::take_hook());
+        let chained = Arc::clone(&previous);
+        panic::set_hook(Box::new(move |info| {
+            let _ = restore_terminal(&mut io::stdout(), alt_screen);
+            chained(info);
+        }));
+        Self { previous }
+    }
+}
+
+impl Drop for PanicHookGuard {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            return;
+        }
+        let previous = Arc::clone(&self.previous);
+        panic::set_hook(Box::new(move |info| previous(info)));
+    }
+}
+
+/// Wyłącza tryb surowy, pokazuje kursor i opuszcza alternatywny ekran;
+/// kolejne wywołania niczego nie psują.
+fn restore_terminal(out: &mut dyn Write, alt_screen: bool) -> io::Result<()> {
+    let _ = terminal::disable_raw_mode();
+    out.execute(cursor::Show)?;
+    if alt_screen {
+        out.execute(terminal::LeaveAlternateScreen)?;
+    }
+    out.flush()
+}
+
+pub(crate) struct RawModeGuard;
 
 impl RawModeGuard {
-    fn new() -> io::Result<Self> {
+    pub(crate) fn new() -> io::Result<Self> {
         terminal::enable_raw_mode()?;
         Ok(Self)
     }
@@ -132,3 +1465,1196 @@ impl Drop for RawModeGuard {
         let _ = terminal::disable_raw_mode();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::path::PathBuf;
+
+    use crossterm::event::{KeyEvent, KeyModifiers};
+    use regex::Regex;
+
+    use super::*;
+    use crate::rules::RuleSet;
+    use crate::span::plain_text;
+    use crate::{JoinMode, load_deck};
+
+    struct ScriptedEvents(VecDeque<Option<Event>>);
+
+    impl ScriptedEvents {
+        fn keys(codes: &[KeyCode]) -> Self {
+            Self(
+                codes
+                    .iter()
+                    .map(|code| Some(Event::Key(KeyEvent::new(*code, KeyModifiers::NONE))))
+                    .collect(),
+            )
+        }
+
+        fn idle_before(mut self, position: usize) -> Self {
+            self.0.insert(position, None);
+            self
+        }
+
+        fn resize_before(mut self, position: usize, columns: u16) -> Self {
+            self.0.insert(position, Some(Event::Resize(columns, 40)));
+            self
+        }
+    }
+
+    impl EventSource for ScriptedEvents {
+        fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+            match self.0.front() {
+                Some(None) => {
+                    self.0.pop_front();
+                    std::thread::sleep(timeout);
+                    Ok(false)
+                }
+                Some(Some(_)) => Ok(true),
+                None => Ok(false),
+            }
+        }
+
+        /// Odczyt czeka bez limitu czasu, więc przestoje przed klawiszem mijają
+        /// niezauważone.
+        fn read(&mut self) -> io::Result<Event> {
+            while let Some(None) = self.0.front() {
+                self.0.pop_front();
+            }
+            self.0
+                .pop_front()
+                .flatten()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "koniec skryptu"))
+        }
+
+        fn rows(&self) -> Option<u16> {
+            None
+        }
+    }
+
+    fn play(codes: &[KeyCode], ending: Option<&Slide>) -> Vec<String> {
+        let config = Config::builder().animations(false).build().unwrap();
+        capture(
+            config,
+            "tests/fixtures/three_lines.txt",
+            ScriptedEvents::keys(codes),
+            ending,
+        )
+        .split('╭')
+        .skip(1)
+        .map(str::to_string)
+        .collect()
+    }
+
+    fn capture(
+        config: Config,
+        deck: &str,
+        mut events: ScriptedEvents,
+        ending: Option<&Slide>,
+    ) -> String {
+        let escapes = Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]").unwrap();
+        escapes
+            .replace_all(&capture_raw(config, deck, &mut events, ending), "")
+            .into_owned()
+    }
+
+    fn capture_raw(
+        mut config: Config,
+        deck: &str,
+        events: &mut ScriptedEvents,
+        ending: Option<&Slide>,
+    ) -> String {
+        let slides = load_deck(
+            &[PathBuf::from(deck)],
+            JoinMode::Contiguous,
+            false,
+            false,
+            &RuleSet::default(),
+        )
+        .unwrap();
+        let mut output = Vec::new();
+        present(
+            &mut config,
+            slides,
+            ending,
+            None,
+            (0, 0),
+            events,
+            &mut output,
+        )
+        .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn watched_directory_picks_up_added_and_removed_files() {
+        let directory =
+            std::env::temp_dir().join(format!("presentation-deck-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("01-alfa.md"), "alfa\n").unwrap();
+        std::fs::write(directory.join("02-beta.md"), "beta\n").unwrap();
+        let inputs = [directory.clone()];
+        let load = || {
+            let sources = crate::sources::expand(&inputs, crate::sources::SortMode::Name)?;
+            load_deck(
+                &sources.scripts,
+                JoinMode::Contiguous,
+                false,
+                false,
+                &RuleSet::default(),
+            )
+        };
+        let reload = DeckReload {
+            load: &load,
+            directories: inputs.to_vec(),
+        };
+
+        let edits = {
+            let directory = directory.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(300));
+                std::fs::write(directory.join("03-gamma.md"), "gamma\n").unwrap();
+                std::fs::remove_file(directory.join("02-beta.md")).unwrap();
+            })
+        };
+        let mut events = ScriptedEvents::keys(&[KeyCode::End, KeyCode::Char('q')]);
+        for _ in 0..15 {
+            events = events.idle_before(0);
+        }
+        let mut config = Config::builder().animations(false).build().unwrap();
+        let mut output = Vec::new();
+        present(
+            &mut config,
+            load().unwrap(),
+            None,
+            Some(&reload),
+            (0, 0),
+            &mut events,
+            &mut output,
+        )
+        .unwrap();
+        edits.join().unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        let output = Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]")
+            .unwrap()
+            .replace_all(&String::from_utf8(output).unwrap(), "")
+            .into_owned();
+        let last = output.rsplit('╭').next().unwrap();
+        assert!(last.contains("gamma"), "{last}");
+        assert!(last.contains("SEQ :: 002/002"), "{last}");
+        assert!(!output.contains("Błąd przeładowania"), "{output}");
+    }
+
+    #[test]
+    fn printed_banner_cycles_from_the_present_loop_until_a_key() {
+        let mut config = Config::builder()
+            .banner_color_cycle(true)
+            .set_title(false)
+            .build()
+            .unwrap();
+        config.set_printed_banner(Some((2, "LOGO".to_string())));
+        let accent = format!("\x1b[3;1H{}{}LOGO", config.color_accent(), BOLD);
+        let glow = format!("\x1b[3;1H{}{}LOGO", config.color_glow(), BOLD);
+        let mut events = ScriptedEvents::keys(&[KeyCode::Right, KeyCode::Char('q')]).idle_before(0);
+        let output = capture_raw(config, "tests/fixtures/three_lines.txt", &mut events, None);
+
+        let cycled = output.find(&accent).expect("baner nie zmienił koloru");
+        let restored = output.rfind(&glow).expect("baner nie wrócił do glow");
+        assert!(cycled < restored);
+        assert!(output[restored..].contains("002/003"));
+    }
+
+    #[test]
+    fn navigates_forward_and_back_then_quits() {
+        let frames = play(
+            &[
+                KeyCode::Right,
+                KeyCode::Right,
+                KeyCode::Left,
+                KeyCode::Char('q'),
+            ],
+            None,
+        );
+
+        let positions: Vec<&str> = frames
+            .iter()
+            .map(|frame| {
+                frame
+                    .split("SEQ :: ")
+                    .nth(1)
+                    .and_then(|rest| rest.split_whitespace().next())
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(positions, ["001/003", "002/003", "003/003", "002/003"]);
+        assert!(frames[2].contains("❝ cytat ❞"));
+    }
+
+    #[test]
+    fn home_and_end_jump_to_the_deck_boundaries() {
+        let frames = play(
+            &[
+                KeyCode::Home,
+                KeyCode::End,
+                KeyCode::End,
+                KeyCode::Home,
+                KeyCode::Char('q'),
+            ],
+            None,
+        );
+
+        let positions: Vec<&str> = frames
+            .iter()
+            .map(|frame| &frame.split("SEQ :: ").nth(1).unwrap()[..7])
+            .collect();
+        assert_eq!(positions, ["001/003", "003/003", "001/003"]);
+    }
+
+    /// Hak działa w tle, więc kolejność dopisanych wierszy nie jest pewna –
+    /// porównywane są posortowane wiersze, gdy pojawią się wszystkie.
+    fn assert_announced(name: &str, codes: &[KeyCode], loop_playback: bool, expected: &[&str]) {
+        let log = std::env::temp_dir().join(format!(
+            "presentation-hook-{}-{}.log",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&log);
+        let config = Config::builder()
+            .animations(false)
+            .loop_playback(loop_playback)
+            .on_slide(Some(format!("echo \"$1 $2\" >> '{}'", log.display())))
+            .build()
+            .unwrap();
+        capture(
+            config,
+            "tests/fixtures/three_slides.txt",
+            ScriptedEvents::keys(codes),
+            None,
+        );
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut contents = String::new();
+        while contents.lines().count() < expected.len() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+            contents = std::fs::read_to_string(&log).unwrap_or_default();
+        }
+        let _ = std::fs::remove_file(&log);
+        let mut lines: Vec<&str> = contents.lines().collect();
+        lines.sort_unstable();
+        assert_eq!(lines, expected, "{name}");
+    }
+
+    #[test]
+    fn on_slide_hook_receives_number_and_heading_of_each_shown_slide() {
+        assert_announced(
+            "next",
+            &[KeyCode::Right, KeyCode::Char('q')],
+            false,
+            &["1 Terminal jako scena", "2 Część druga"],
+        );
+        assert_announced(
+            "jump",
+            &[KeyCode::Char('3'), KeyCode::Enter, KeyCode::Char('q')],
+            false,
+            &["1 Terminal jako scena", "3 Podsumowanie"],
+        );
+        assert_announced(
+            "loop",
+            &[KeyCode::End, KeyCode::Right, KeyCode::Char('q')],
+            true,
+            &[
+                "1 Terminal jako scena",
+                "1 Terminal jako scena",
+                "3 Podsumowanie",
+            ],
+        );
+    }
+
+    #[test]
+    fn tab_keys_jump_between_slides_starting_with_headings() {
+        let config = Config::builder().animations(false).build().unwrap();
+        let output = capture(
+            config,
+            "tests/fixtures/outline.txt",
+            ScriptedEvents::keys(&[
+                KeyCode::Tab,
+                KeyCode::Tab,
+                KeyCode::Tab,
+                KeyCode::Tab,
+                KeyCode::BackTab,
+                KeyCode::BackTab,
+                KeyCode::BackTab,
+                KeyCode::BackTab,
+                KeyCode::Char('q'),
+            ]),
+            None,
+        );
+
+        let positions: Vec<&str> = output
+            .split('╭')
+            .skip(1)
+            .map(|frame| &frame.split("SEQ :: ").nth(1).unwrap()[..7])
+            .collect();
+        assert_eq!(
+            positions,
+            [
+                "001/006", "002/006", "004/006", "006/006", "004/006", "002/006", "001/006"
+            ]
+        );
+    }
+
+    #[test]
+    fn vim_keys_navigate_like_arrows_home_and_end() {
+        let frames = play(
+            &[
+                KeyCode::Char('l'),
+                KeyCode::Char('j'),
+                KeyCode::Char('k'),
+                KeyCode::Char('G'),
+                KeyCode::Char('g'),
+                KeyCode::Char('h'),
+                KeyCode::Char('g'),
+                KeyCode::Char('g'),
+                KeyCode::Char('q'),
+            ],
+            None,
+        );
+
+        let positions: Vec<&str> = frames
+            .iter()
+            .map(|frame| &frame.split("SEQ :: ").nth(1).unwrap()[..7])
+            .collect();
+        assert_eq!(
+            positions,
+            [
+                "001/003", "002/003", "003/003", "002/003", "003/003", "002/003", "001/003"
+            ]
+        );
+    }
+
+    #[test]
+    fn typed_number_jumps_to_slide_on_enter() {
+        let frames = play(
+            &[
+                KeyCode::Char('3'),
+                KeyCode::Enter,
+                KeyCode::Char('1'),
+                KeyCode::Backspace,
+                KeyCode::Char('9'),
+                KeyCode::Char('9'),
+                KeyCode::Enter,
+                KeyCode::Char('2'),
+                KeyCode::Enter,
+                KeyCode::Char('1'),
+                KeyCode::Esc,
+                KeyCode::Enter,
+                KeyCode::Char('q'),
+            ],
+            None,
+        );
+
+        let positions: Vec<&str> = frames
+            .iter()
+            .map(|frame| &frame.split("SEQ :: ").nth(1).unwrap()[..7])
+            .collect();
+        assert_eq!(
+            positions,
+            [
+                "001/003", "001/003", "003/003", "003/003", "003/003", "003/003", "003/003",
+                "003/003", "003/003", "002/003", "002/003", "002/003", "003/003"
+            ]
+        );
+        assert!(frames[1].contains("IDŹ DO :: 3_"));
+        assert!(frames[6].contains("IDŹ DO :: 99_"));
+        assert!(!frames[11].contains("IDŹ DO"));
+    }
+
+    #[test]
+    fn shows_closing_slide_and_exits_on_next_key() {
+        let ending = Slide::new(
+            vec![Segment::new(SegmentKind::Heading(vec![
+                crate::span::Span::plain("FIN"),
+            ]))],
+            PathBuf::new(),
+            None,
+        );
+        let frames = play(
+            &[
+                KeyCode::Enter,
+                KeyCode::Enter,
+                KeyCode::Enter,
+                KeyCode::Char('x'),
+            ],
+            Some(&ending),
+        );
+
+        assert_eq!(frames.len(), 4);
+        assert!(frames[3].contains("FIN"));
+    }
+
+    #[test]
+    fn enter_follows_configured_action() {
+        let frames = |action| {
+            let config = Config::builder()
+                .animations(false)
+                .enter_action(action)
+                .build()
+                .unwrap();
+            capture(
+                config,
+                "tests/fixtures/three_lines.txt",
+                ScriptedEvents::keys(&[KeyCode::Enter, KeyCode::Right, KeyCode::Char('q')]),
+                None,
+            )
+            .split("SEQ :: ")
+            .skip(1)
+            .map(|rest| rest[..7].to_string())
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(frames(EnterAction::Next), ["001/003", "002/003", "003/003"]);
+        assert_eq!(
+            frames(EnterAction::Reveal),
+            ["001/003", "001/003", "002/003"]
+        );
+        assert_eq!(frames(EnterAction::Noop), ["001/003", "002/003"]);
+    }
+
+    #[test]
+    fn code_blocks_keep_indentation_and_mark_cut_lines() {
+        let config = Config::builder()
+            .animations(false)
+            .frame_width(40)
+            .build()
+            .unwrap();
+        let output = capture(
+            config,
+            "tests/fixtures/code.txt",
+            ScriptedEvents::keys(&[KeyCode::Right, KeyCode::Char('q')]),
+            None,
+        );
+        let code = output.split('╭').nth(2).unwrap();
+
+        assert!(code.contains("002 :: fn main() {"));
+        assert!(code.contains("002 ::     println!(\"linia kodu d"));
+        assert!(code.contains("»│"));
+        assert!(code.contains("002 :: }"));
+        assert!(!code.contains('›'));
+    }
+
+    #[test]
+    fn wrap_spreads_long_lines_over_framed_rows() {
+        let config = Config::builder()
+            .animations(false)
+            .frame_width(60)
+            .wrap(true)
+            .build()
+            .unwrap();
+        let output = capture(
+            config,
+            "tests/fixtures/long_line.txt",
+            ScriptedEvents::keys(&[KeyCode::Right, KeyCode::Char('q')]),
+            None,
+        );
+        let rows = |frame: usize| -> Vec<String> {
+            let frame = output.split('╭').nth(frame).unwrap();
+            let frame = &frame[..frame.find('╰').unwrap()];
+            frame.lines().skip(1).map(str::to_string).collect()
+        };
+        let prose = rows(1);
+        let bullet = rows(2);
+
+        assert_eq!(prose.len(), 5);
+        assert!(prose[0].starts_with("│ 001 :: kadry"));
+        assert!(prose[1].starts_with("│        kadry"));
+        assert!(prose[4].starts_with("│        kadry ok "));
+        assert_eq!(bullet.len(), 2);
+        assert!(bullet[0].starts_with("│ 002 :: • punkt"));
+        assert!(bullet[1].starts_with("│          "));
+        assert!(!output.contains('›'));
+    }
+
+    #[test]
+    fn wide_glyphs_keep_the_right_border_aligned() {
+        let config = Config::builder()
+            .animations(false)
+            .frame_width(30)
+            .build()
+            .unwrap();
+        let output = capture(
+            config,
+            "tests/fixtures/wide_glyphs.txt",
+            ScriptedEvents::keys(&[KeyCode::Right, KeyCode::Char('q')]),
+            None,
+        );
+        let rows: Vec<&str> = output
+            .lines()
+            .filter(|line| line.starts_with('│'))
+            .collect();
+
+        assert!(rows[0].contains("• 日本語 🚀 test"));
+        assert!(rows.iter().any(|row| row.contains("日本語日本語日本›")));
+        for row in rows {
+            assert_eq!(crate::visible_width(row), 30, "{row}");
+            assert!(row.ends_with('│'));
+        }
+    }
+
+    #[test]
+    fn compact_mode_renders_lines_without_frame() {
+        let config = Config::builder()
+            .animations(false)
+            .compact_mode(true)
+            .build()
+            .unwrap();
+        let output = capture(
+            config,
+            "tests/fixtures/three_lines.txt",
+            ScriptedEvents::keys(&[KeyCode::Right, KeyCode::Char('q')]),
+            None,
+        );
+
+        assert!(output.contains("• punkt\n"));
+        assert!(!output.contains(['╭', '╰', '│']));
+        assert!(!output.contains("001 ::"));
+    }
+
+    #[test]
+    fn long_tokens_are_cut_on_one_row_at_any_frame_width() {
+        for width in [40, 80, 120] {
+            let config = Config::builder()
+                .animations(false)
+                .frame_width(width)
+                .build()
+                .unwrap();
+            let output = capture(
+                config,
+                "tests/fixtures/long_url.txt",
+                ScriptedEvents::keys(&[KeyCode::Right, KeyCode::Char('q')]),
+                None,
+            );
+            let rows: Vec<&str> = output
+                .lines()
+                .filter(|line| line.starts_with("│ 00"))
+                .collect();
+
+            assert_eq!(rows.len(), 2);
+            assert!(rows[1].contains("https://example.com/aaa"));
+            assert!(rows[1].contains('›'));
+            assert_eq!(visible_width(rows[1]), visible_width(rows[0]));
+        }
+    }
+
+    #[test]
+    fn slide_theme_applies_to_its_own_frame_only() {
+        let config = Config::builder().animations(false).build().unwrap();
+        let output = capture(
+            config,
+            "tests/fixtures/slide_theme.txt",
+            ScriptedEvents::keys(&[KeyCode::Right, KeyCode::Right, KeyCode::Char('q')]),
+            None,
+        );
+        let frames: Vec<&str> = output.split('╭').skip(1).collect();
+
+        assert!(!frames[0].contains("THEME ::"));
+        assert!(frames[1].contains("THEME :: AMBER"));
+        assert!(frames[2].contains("Nieznany motyw sekwencji „nieistniejacy”"));
+        assert!(!frames[2].contains("THEME ::"));
+    }
+
+    #[test]
+    fn slide_theme_file_resolves_next_to_the_slide_once_at_load() {
+        let directory =
+            std::env::temp_dir().join(format!("presentation-themed-{}", std::process::id()));
+        std::fs::create_dir_all(directory.join("motywy")).unwrap();
+        let deck = directory.join("slajdy.txt");
+        std::fs::write(&deck, "@theme motywy/kawa.toml\n# Przerwa\n").unwrap();
+        let theme = directory.join("motywy/kawa.toml");
+        std::fs::write(
+            &theme,
+            "name = \"Kawa\"\naccent = \"#c08552\"\ndim = \"#5e3023\"\nglow = \"#f3e9dc\"\n",
+        )
+        .unwrap();
+        let slides = load_deck(
+            &[deck],
+            JoinMode::Contiguous,
+            false,
+            false,
+            &RuleSet::default(),
+        )
+        .unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        let mut config = Config::builder().animations(false).build().unwrap();
+        let mut output = Vec::new();
+        present(
+            &mut config,
+            slides,
+            None,
+            None,
+            (0, 0),
+            &mut ScriptedEvents::keys(&[KeyCode::Char('q')]),
+            &mut output,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("THEME ::"), "{output:?}");
+        assert!(output.contains("KAWA"), "{output:?}");
+        assert!(output.contains("\x1b[38;2;192;133;82m"), "{output:?}");
+        assert!(!output.contains("Nieznany motyw"), "{output:?}");
+    }
+
+    #[test]
+    fn idle_dim_fades_the_frame_until_the_next_key() {
+        let config = Config::builder()
+            .animations(false)
+            .idle_dim(Duration::from_millis(20))
+            .build()
+            .unwrap();
+        let accent = config.color_accent().to_string();
+        let mut events = ScriptedEvents::keys(&[KeyCode::Right, KeyCode::Char('q')]).idle_before(0);
+        let output = capture_raw(config, "tests/fixtures/three_lines.txt", &mut events, None);
+        let frames: Vec<&str> = output.split('╭').skip(1).collect();
+
+        assert_eq!(frames.len(), 3);
+        assert!(frames[0].contains(&accent));
+        assert!(!frames[1].contains(&accent));
+        assert!(frames[2].contains(&accent));
+        assert!(frames[2].contains("001/003"));
+    }
+
+    #[test]
+    fn auto_advance_steps_through_the_deck_and_stops_on_the_last_slide() {
+        let config = Config::builder()
+            .animations(false)
+            .auto_advance(Duration::from_millis(20))
+            .build()
+            .unwrap();
+        let events = ScriptedEvents::keys(&[KeyCode::Left, KeyCode::Char('q')])
+            .idle_before(0)
+            .idle_before(0);
+        let positions: Vec<String> =
+            capture(config, "tests/fixtures/three_lines.txt", events, None)
+                .split("SEQ :: ")
+                .skip(1)
+                .map(|rest| rest[..7].to_string())
+                .collect();
+
+        assert_eq!(positions, ["001/003", "002/003", "003/003", "002/003"]);
+    }
+
+    #[test]
+    fn pause_at_holds_auto_advance_until_a_key_is_pressed() {
+        let config = Config::builder()
+            .animations(false)
+            .auto_advance(Duration::from_millis(20))
+            .pause_at(vec![2])
+            .build()
+            .unwrap();
+        let events = ScriptedEvents::keys(&[KeyCode::Left, KeyCode::Char('q')])
+            .idle_before(0)
+            .idle_before(0);
+        let positions: Vec<String> =
+            capture(config, "tests/fixtures/three_lines.txt", events, None)
+                .split("SEQ :: ")
+                .skip(1)
+                .map(|rest| rest[..7].to_string())
+                .collect();
+
+        assert_eq!(positions, ["001/003", "002/003", "001/003"]);
+    }
+
+    #[test]
+    fn loop_wraps_around_both_ends_of_the_deck() {
+        let config = Config::builder()
+            .animations(false)
+            .loop_playback(true)
+            .build()
+            .unwrap();
+        let output = capture(
+            config,
+            "tests/fixtures/three_lines.txt",
+            ScriptedEvents::keys(&[
+                KeyCode::Left,
+                KeyCode::Right,
+                KeyCode::Right,
+                KeyCode::Char('q'),
+            ]),
+            None,
+        );
+        let positions: Vec<&str> = output
+            .split("SEQ :: ")
+            .skip(1)
+            .map(|rest| &rest[..7])
+            .collect();
+
+        assert_eq!(positions, ["001/003", "003/003", "001/003", "002/003"]);
+        assert!(output.contains("LOOP :: ↻"));
+    }
+
+    #[test]
+    fn notes_out_receives_the_current_slide_notes() {
+        let path =
+            std::env::temp_dir().join(format!("presentation-notes-{}.txt", std::process::id()));
+        let config = Config::builder()
+            .animations(false)
+            .notes_out(Some(path.clone()))
+            .build()
+            .unwrap();
+        capture(
+            config,
+            "tests/fixtures/notes.txt",
+            ScriptedEvents::keys(&[KeyCode::Char('q')]),
+            None,
+        );
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let mut page = String::new();
+        while !page.ends_with("RustLab.\n") && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+            page = std::fs::read_to_string(&path).unwrap_or_default();
+        }
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(
+            page,
+            "SEQ :: 001/002  CZAS :: 00:00\n\nPrzywitać się z salą.\nWspomnieć o RustLab.\n"
+        );
+    }
+
+    #[test]
+    fn notes_out_failure_is_reported_in_the_footer() {
+        let config = Config::builder()
+            .animations(false)
+            .notes_out(Some(std::env::temp_dir()))
+            .build()
+            .unwrap();
+        let output = capture(
+            config,
+            "tests/fixtures/notes.txt",
+            ScriptedEvents::keys(&[KeyCode::Char('q')]).idle_before(0),
+            None,
+        );
+
+        assert_eq!(output.matches("Nie udało się zapisać notatek").count(), 1);
+    }
+
+    #[test]
+    fn budget_counts_down_then_warns_and_reports_overtime() {
+        let config = Config::builder().build().unwrap();
+        let line = |elapsed| {
+            let mut output = Vec::new();
+            print_budget(
+                &mut output,
+                &config,
+                Duration::from_secs(90),
+                Duration::from_secs(elapsed),
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        assert!(line(0).contains(&format!("{}01:30", config.color_glow())));
+        assert!(line(70).contains(&format!("{}00:20", BUDGET_WARNING)));
+        assert!(line(95).contains(&format!("{}OVERTIME", BUDGET_OVER)));
+    }
+
+    #[test]
+    fn total_budget_is_drawn_only_in_presenter_view() {
+        let frame = |presenter| {
+            let config = Config::builder()
+                .animations(false)
+                .total_budget(Some(Duration::from_secs(20 * 60)))
+                .presenter_view(presenter)
+                .build()
+                .unwrap();
+            let ticks = needs_tick(&config, &[]);
+            let output = capture(
+                config,
+                "tests/fixtures/three_lines.txt",
+                ScriptedEvents::keys(&[KeyCode::Char('q')]),
+                None,
+            );
+            (ticks, output)
+        };
+
+        let (ticks, output) = frame(false);
+        assert!(!ticks);
+        assert!(!output.contains("TOTAL ::"), "{output}");
+        let (ticks, output) = frame(true);
+        assert!(ticks);
+        assert!(output.contains("TOTAL ::"), "{output}");
+    }
+
+    #[test]
+    fn presenter_view_lists_notes_and_highlights_the_current_cue() {
+        let notes: Vec<String> = ["[0:00] powitanie", "[1:30] demo", "luźna uwaga"]
+            .iter()
+            .map(|note| note.to_string())
+            .collect();
+        let config = Config::builder().build().unwrap();
+        let mut output = Vec::new();
+        print_presenter_notes(&mut output, &config, &notes, 0, Duration::from_secs(100)).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert!(lines[0].contains("NOTES ::"));
+        assert!(lines[1].contains(&format!("{}powitanie", config.color_accent())));
+        assert!(lines[2].contains("[01:30]"));
+        assert!(lines[2].contains(&format!("{}{}demo", config.color_glow(), BOLD)));
+        assert!(lines[3].contains(&format!("{}luźna uwaga", config.color_accent())));
+
+        let frame = |presenter| {
+            let config = Config::builder()
+                .animations(false)
+                .presenter_view(presenter)
+                .build()
+                .unwrap();
+            capture(
+                config,
+                "tests/fixtures/notes.txt",
+                ScriptedEvents::keys(&[KeyCode::Char('q')]),
+                None,
+            )
+        };
+        assert!(!frame(false).contains("NOTES ::"));
+        let shown = frame(true);
+        assert!(shown.contains("NOTES ::"), "{shown}");
+        assert!(shown.contains("  Przywitać się z salą."), "{shown}");
+    }
+
+    #[test]
+    fn arrow_keys_scroll_presenter_notes_and_slide_change_resets_them() {
+        let config = Config::builder()
+            .animations(false)
+            .presenter_view(true)
+            .build()
+            .unwrap();
+        let output = capture(
+            config,
+            "tests/fixtures/long_notes.txt",
+            ScriptedEvents::keys(&[
+                KeyCode::Down,
+                KeyCode::Down,
+                KeyCode::Down,
+                KeyCode::Up,
+                KeyCode::Right,
+                KeyCode::Char('q'),
+            ]),
+            None,
+        );
+        let headers: Vec<&str> = output
+            .lines()
+            .filter(|line| line.starts_with("NOTES"))
+            .collect();
+
+        assert_eq!(
+            headers,
+            [
+                "NOTES (1/7) ::",
+                "NOTES (2/7) ::",
+                "NOTES (3/7) ::",
+                "NOTES (2/7) ::",
+                "NOTES (1/6) ::",
+            ]
+        );
+        let last = output.rsplit("NOTES").next().unwrap();
+        assert!(last.contains("pięć") && !last.contains("sześć"), "{last}");
+    }
+
+    #[test]
+    fn pace_compares_elapsed_time_with_the_budget() {
+        let budget = Duration::from_secs(20 * 60);
+        assert_eq!(pace(Duration::ZERO, budget), Pace::Ok);
+        assert_eq!(pace(Duration::from_secs(14 * 60), budget), Pace::Ok);
+        assert_eq!(pace(Duration::from_secs(15 * 60), budget), Pace::Warning);
+        assert_eq!(pace(budget, budget), Pace::Over);
+        assert_eq!(pace(Duration::from_secs(25 * 60), budget), Pace::Over);
+
+        let config = Config::builder().no_color(true).build().unwrap();
+        let mut output = Vec::new();
+        print_total_budget(&mut output, &config, budget, Duration::from_secs(25 * 60)).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "TOTAL :: ██████████ 25:00/20:00\n"
+        );
+    }
+
+    #[test]
+    fn theme_background_spans_every_framed_row() {
+        let config = Config::builder().animations(false).build().unwrap();
+        let background = config.color_background().to_string();
+        let border = format!("{}{}", background, config.color_dim());
+        let mut events = ScriptedEvents::keys(&[KeyCode::Char('q')]);
+        let output = capture_raw(config, "tests/fixtures/three_lines.txt", &mut events, None);
+        let rows: Vec<&str> = output
+            .lines()
+            .filter(|line| line.contains(['╭', '│', '╰']))
+            .collect();
+
+        assert!(!background.is_empty());
+        assert_eq!(rows.len(), 3);
+        for row in rows {
+            assert!(row.contains(&border));
+            assert!(row.trim_end().ends_with(RESET));
+            assert_eq!(row.matches(RESET).count(), 1);
+        }
+
+        let config = Config::builder()
+            .animations(false)
+            .no_color(true)
+            .build()
+            .unwrap();
+        let mut events = ScriptedEvents::keys(&[KeyCode::Char('q')]);
+        let output = capture_raw(config, "tests/fixtures/three_lines.txt", &mut events, None);
+        assert!(!output.contains(&background));
+    }
+
+    #[test]
+    fn responsive_frame_follows_resizes_until_manual_adjustment() {
+        let config = Config::builder()
+            .animations(false)
+            .frame_width(60)
+            .responsive(true)
+            .footer_fields(vec![FooterField::Frame])
+            .build()
+            .unwrap();
+        let events = ScriptedEvents::keys(&[KeyCode::Char('-'), KeyCode::Char('q')])
+            .resize_before(0, 30)
+            .resize_before(1, 90)
+            .resize_before(3, 120);
+        let output = capture(config, "tests/fixtures/three_lines.txt", events, None);
+        let widths: Vec<&str> = output
+            .split("FRAME :: ")
+            .skip(1)
+            .map(|footer| footer.split_whitespace().next().unwrap())
+            .collect();
+
+        assert_eq!(widths, ["60", "40", "90", "88", "88"]);
+    }
+
+    #[test]
+    fn progress_bar_spans_the_frame_and_follows_resizes() {
+        let config = Config::builder()
+            .animations(false)
+            .frame_width(40)
+            .responsive(true)
+            .progress_bar(true)
+            .build()
+            .unwrap();
+        let events =
+            ScriptedEvents::keys(&[KeyCode::Right, KeyCode::Char('q')]).resize_before(1, 60);
+        let output = capture(config, "tests/fixtures/three_lines.txt", events, None);
+        let bars: Vec<&str> = output.lines().filter(|line| line.ends_with('%')).collect();
+
+        assert_eq!(bars.len(), 3);
+        assert_eq!(visible_width(bars[0]), 40);
+        assert!(bars[0].starts_with(&format!("{}░", "█".repeat(11))));
+        assert!(bars[0].ends_with("  33%"));
+        assert_eq!(visible_width(bars[2]), 60);
+        assert!(bars[2].ends_with("  66%"));
+    }
+
+    #[test]
+    fn window_title_follows_the_slide_heading() {
+        let config = Config::builder()
+            .animations(false)
+            .set_title(true)
+            .build()
+            .unwrap();
+        let fallback = format!("\x1b]0;{}\x07", config.presentation_title());
+        let mut events = ScriptedEvents::keys(&[KeyCode::Right, KeyCode::Char('q')]);
+        let output = capture_raw(config, "tests/fixtures/three_lines.txt", &mut events, None);
+        let frames: Vec<&str> = output.split('╭').collect();
+
+        assert!(frames[0].contains("\x1b]0;Wstęp\x07"));
+        assert!(frames[1].contains(&fallback));
+    }
+
+    #[test]
+    fn restoring_the_terminal_is_idempotent() {
+        let restore = || {
+            let mut output = Vec::new();
+            restore_terminal(&mut output, true).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        let first = restore();
+
+        assert!(first.contains("\x1b[?25h"));
+        assert!(first.contains("\x1b[?1049l"));
+        assert_eq!(restore(), first);
+    }
+
+    #[test]
+    fn vcenter_pads_short_slides_but_never_tall_ones() {
+        assert_eq!(vertical_padding(40, 10), 15);
+        assert_eq!(vertical_padding(40, 11), 14);
+        assert_eq!(vertical_padding(20, 35), 0);
+
+        let config = Config::builder()
+            .animations(false)
+            .vcenter(true)
+            .build()
+            .unwrap();
+        let mut events = ScriptedEvents::keys(&[KeyCode::Char('q')]).resize_before(0, 120);
+        let output = capture_raw(config, "tests/fixtures/three_lines.txt", &mut events, None);
+        let moves = Regex::new(r"\x1b\[(\d+);1H").unwrap();
+        let last = moves.captures_iter(&output).last().unwrap();
+        let row: usize = last[1].parse().unwrap();
+        let height = output[last.get(0).unwrap().end()..].matches('\n').count();
+
+        assert!(height > 0);
+        assert_eq!(row - 1, (40 - height) / 2);
+    }
+
+    #[test]
+    fn page_keys_scroll_tall_slides_before_leaving_them() {
+        let config = Config::builder()
+            .animations(false)
+            .set_title(false)
+            .build()
+            .unwrap();
+        let events = ScriptedEvents::keys(&[
+            KeyCode::PageDown,
+            KeyCode::Char('j'),
+            KeyCode::PageUp,
+            KeyCode::Char('k'),
+            KeyCode::Char('j'),
+            KeyCode::Char('j'),
+            KeyCode::Char('q'),
+        ])
+        .resize_before(0, 80);
+        let frames: Vec<String> = capture(config, "tests/fixtures/tall.txt", events, None)
+            .split('╭')
+            .skip(1)
+            .map(str::to_string)
+            .collect();
+        let position = |frame: &String| frame.split("SEQ :: ").nth(1).unwrap()[..7].to_string();
+
+        assert!(!frames[0].contains(MORE_MARKER));
+        assert!(frames[1].contains(MORE_MARKER));
+        assert!(frames[1].contains("• punkt 1 "));
+        assert!(!frames[1].contains("• punkt 45"));
+        assert!(frames[1].lines().count() < 40);
+        assert!(!frames[2].contains(MORE_MARKER));
+        assert!(!frames[2].contains("• punkt 1 "));
+        assert!(frames[2].contains("• punkt 45"));
+        assert!(frames[4].contains("• punkt 1 "));
+        assert!(frames[5].contains("• punkt 45"));
+        let positions: Vec<String> = frames.iter().map(position).collect();
+        assert_eq!(
+            positions,
+            [
+                "001/002", "001/002", "001/002", "002/002", "001/002", "001/002", "002/002"
+            ]
+        );
+    }
+
+    #[test]
+    fn tags_slides_with_source_file_and_local_index() {
+        let paths = [
+            PathBuf::from("tests/fixtures/three_lines.txt"),
+            PathBuf::from("tests/fixtures/deck/10-outro.md"),
+        ];
+        let slides = load_deck(
+            &paths,
+            JoinMode::Contiguous,
+            false,
+            false,
+            &RuleSet::default(),
+        )
+        .unwrap();
+
+        let labels: Vec<String> = slides.iter().map(|slide| slide.source_label()).collect();
+        assert_eq!(
+            labels,
+            [
+                "three_lines.txt #1",
+                "three_lines.txt #2",
+                "three_lines.txt #3",
+                "10-outro.md #1",
+                "10-outro.md #2",
+            ]
+        );
+        assert_eq!(slides[3].source(), paths[1]);
+        assert_eq!(slides[3].index_in_source(), Some(1));
+    }
+
+    #[test]
+    fn leaves_join_separators_without_local_index() {
+        let paths = [
+            PathBuf::from("tests/fixtures/three_lines.txt"),
+            PathBuf::from("tests/fixtures/deck/10-outro.md"),
+        ];
+        let slides = load_deck(
+            &paths,
+            JoinMode::Separator,
+            false,
+            false,
+            &RuleSet::default(),
+        )
+        .unwrap();
+
+        assert_eq!(slides.len(), 6);
+        assert_eq!(slides[3].index_in_source(), None);
+        assert_eq!(slides[4].source_label(), "10-outro.md #1");
+    }
+
+    #[test]
+    fn contents_slide_lists_headings_with_their_slide_numbers() {
+        let config = Config::builder()
+            .animations(false)
+            .frame_width(40)
+            .no_color(true)
+            .build()
+            .unwrap();
+        let slides = with_contents(
+            load_deck(
+                &[PathBuf::from("tests/fixtures/outline.txt")],
+                JoinMode::Contiguous,
+                false,
+                false,
+                &RuleSet::default(),
+            )
+            .unwrap(),
+        );
+        let entries: Vec<(String, usize)> = slides[0]
+            .segments()
+            .iter()
+            .filter_map(|segment| match segment.kind() {
+                SegmentKind::Contents { title, slide } => Some((plain_text(title), *slide)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(slides.len(), 7);
+        assert_eq!(entries, [("Wstęp".to_string(), 3), ("Plan".to_string(), 5)]);
+        assert_eq!(slides[2].heading().as_deref(), Some("Wstęp"));
+
+        let row = |title: &str| {
+            let entry = Segment::new(SegmentKind::Contents {
+                title: vec![Span::plain(title)],
+                slide: 3,
+            });
+            let mut out = Vec::new();
+            animate_line(&mut out, &config, 0, &entry, false).unwrap();
+            let row = String::from_utf8(out).unwrap().trim_end().to_string();
+            assert_eq!(visible_width(&row), 40, "{row}");
+            row
+        };
+        assert!(row("Wstęp").contains("Wstęp …"));
+        assert!(row("Wstęp").ends_with("… 03│"));
+        assert!(row("Bardzo długi nagłówek, który się nie mieści").contains("› …… 03│"));
+
+        let plain = load_deck(
+            &[PathBuf::from("tests/fixtures/wrapped.txt")],
+            JoinMode::Contiguous,
+            false,
+            false,
+            &RuleSet::default(),
+        )
+        .unwrap();
+        let count = plain.len();
+        assert_eq!(with_contents(plain).len(), count);
+    }
+}
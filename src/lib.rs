@@ -0,0 +1,3854 @@
+use std::borrow::Cow;
+use std::env;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use clap::{Parser, ValueEnum};
+use crossterm::cursor;
+use crossterm::terminal::{self, ClearType};
+use crossterm::{Command, QueueableCommand};
+use dotenvy::dotenv;
+use regex::Regex;
+
+#[doc(hidden)]
+pub mod bench;
+mod clock;
+mod config;
+mod deck_info;
+mod demo;
+mod export;
+mod hooks;
+#[cfg(feature = "image")]
+mod image_palette;
+mod interaction;
+mod notes;
+mod replay;
+mod rules;
+#[cfg(feature = "snapshot")]
+mod snapshot;
+mod sources;
+mod span;
+#[cfg(feature = "syntax")]
+mod syntax;
+mod table;
+mod theme;
+mod watch;
+
+pub use crate::config::ConfigBuilder;
+use crate::interaction::{DeckReload, Slide, run_presentation, with_contents};
+use crate::rules::{ColorSlot, RuleSet, RuleStyle};
+use crate::sources::SortMode;
+use crate::span::{
+    FittedLine, Span, SpanStyle, fit_spans, highlight_spans, parse_inline, plain_text, spans_width,
+    wrap_spans,
+};
+use crate::theme::{ThemeGlyphs, ThemePalette};
+
+const RESET: &str = "\x1b[0m";
+const RESET_KEEP_BACKGROUND: &str = "\x1b[22;23;24;27;29;39m";
+const BOLD: &str = "\x1b[1m";
+const ITALIC: &str = "\x1b[3m";
+const UNDERLINE: &str = "\x1b[4m";
+const REVERSE: &str = "\x1b[7m";
+const MIN_FRAME_WIDTH: usize = 40;
+const DEFAULT_FRAME_WIDTH: usize = 120;
+const SCAN_BEAM: &str = "\x1b[97m";
+const BANNER_SWEEP_BUDGET: Duration = Duration::from_millis(1500);
+const BANNER_SWEEP_STEP: Duration = Duration::from_millis(8);
+pub(crate) const BANNER_CYCLE_STEP: Duration = Duration::from_millis(700);
+const BANNER_CYCLE_SLOTS: [ColorSlot; 3] = [ColorSlot::Glow, ColorSlot::Accent, ColorSlot::Dim];
+const FRAME_TITLE_DIRECTIVE: &str = "@frame-title";
+const THEME_DIRECTIVE: &str = "@theme";
+const INSTANT_DIRECTIVE: &str = "@instant";
+const SPACE_DIRECTIVE: &str = "@space";
+const BANNER_HEADING_DIRECTIVE: &str = "@banner-heading";
+const NOTES_MARKER: &str = "???";
+const BUDGET_DIRECTIVE: &str = "@budget";
+const ALIGN_MARKERS: [(&str, Align); 3] = [
+    (":<:", Align::Left),
+    (":^:", Align::Center),
+    (":>:", Align::Right),
+];
+const MAX_SPACER_ROWS: usize = 20;
+const MAX_LIST_DIGITS: usize = 9;
+const MAX_BULLET_INDENT: usize = 4;
+const BULLET_INDENT: &str = "  ";
+const NESTED_BULLETS: [&str; 2] = ["◦", "▪"];
+pub(crate) const CONTENTS_LEADER: &str = "…";
+pub(crate) const QUOTE_PREFIX: &str = "  ▌ ";
+const CODE_FENCE: &str = "```";
+const CODE_TAB: &str = "    ";
+const SCROLL_MARKER: &str = "»";
+
+#[derive(Parser, Debug)]
+#[command(
+    author = "RustLab",
+    version,
+    about = "Retro-futurystyczny silnik prezentacyjny dla terminala",
+    disable_help_subcommand = true
+)]
+struct Cli {
+    /// Pliki lub katalogi z treścią prezentacji (łączone w jedną prezentację)
+    #[arg(required_unless_present = "generate_demo", num_args = 1..)]
+    scripts: Vec<PathBuf>,
+    /// Kolejność plików .txt/.md wczytywanych z katalogu
+    #[arg(long, value_enum, default_value_t = SortMode::Name)]
+    sort: SortMode,
+    /// Sposób łączenia wielu plików: jednym ciągiem lub z separatorem
+    #[arg(long, value_enum, default_value_t = JoinMode::Contiguous)]
+    join: JoinMode,
+    /// Ścieżka do pliku baneru ASCII
+    #[arg(short, long)]
+    banner: Option<PathBuf>,
+    /// Maksymalna szerokość baneru w kolumnach (domyślnie szerokość terminala)
+    #[arg(long)]
+    banner_width: Option<usize>,
+    /// Pozostawienie baneru nad ramką przez całą prezentację
+    #[arg(long)]
+    sticky_banner: bool,
+    /// Odsłanianie baneru jasną linią skanującą przesuwaną od lewej do prawej
+    #[arg(long)]
+    banner_gradient: bool,
+    /// Powolne przełączanie koloru baneru między kolorami motywu do naciśnięcia klawisza
+    #[arg(long)]
+    animate_banner_color_cycle: bool,
+    /// Automatyczne przewijanie sekwencji według czasów zapisanych w pliku (sekundy, jedna linia na sekwencję)
+    #[arg(long, value_name = "LOG")]
+    replay: Option<PathBuf>,
+    /// Numery sekwencji (od 1, rozdzielone przecinkami), na których --replay i --auto-advance czekają na klawisz
+    #[arg(long, value_name = "NUMERY", value_delimiter = ',')]
+    pause_at: Vec<usize>,
+    /// Nagłówki rysowane jako wypełnione paski na całą szerokość ramki
+    #[arg(long)]
+    heading_banner: bool,
+    /// Wyłącza zamianę nagłówków i etykiet na wielkie litery
+    #[arg(long)]
+    no_upcase_headings: bool,
+    /// Wiersz z datą modyfikacji skryptu, wersją programu i commitem git nad prezentacją
+    #[arg(long)]
+    deck_info: bool,
+    /// Nadpisanie tytułu prezentacji
+    #[arg(short, long)]
+    title: Option<String>,
+    /// Nadpisanie szerokości ramki
+    #[arg(long)]
+    frame_width: Option<usize>,
+    /// Dopasowanie szerokości ramki do terminala przy każdej zmianie rozmiaru okna
+    /// (do pierwszej ręcznej zmiany klawiszami +/-)
+    #[arg(long)]
+    responsive: bool,
+    /// Wybór motywu kolorystycznego
+    #[arg(long, value_enum)]
+    theme: Option<ThemeName>,
+    /// Ścieżka do pliku motywu w formacie TOML
+    #[arg(long)]
+    theme_path: Option<PathBuf>,
+    /// Motyw wyznaczony z dominujących kolorów obrazu (PNG/JPEG)
+    #[cfg(feature = "image")]
+    #[arg(long, value_name = "OBRAZ", conflicts_with = "theme_path")]
+    palette_from_image: Option<PathBuf>,
+    /// Przeładowanie motywu przy każdej zmianie pliku z --theme-path
+    #[arg(long, requires = "theme_path")]
+    watch_theme: bool,
+    /// Krzywa tempa animacji pisania (przyspieszanie lub zwalnianie)
+    #[arg(long, value_enum)]
+    ease: Option<Easing>,
+    /// Natychmiastowe renderowanie (bez animacji)
+    #[arg(long)]
+    instant: bool,
+    /// Wyjście bez kolorów i stylów ANSI (także zmienna NO_COLOR)
+    #[arg(long)]
+    no_color: bool,
+    /// Tytuł okna terminala podąża za nagłówkiem bieżącej sekwencji (domyślnie)
+    #[arg(long, overrides_with = "no_set_title")]
+    set_title: bool,
+    /// Pozostawia tytuł okna terminala bez zmian
+    #[arg(long)]
+    no_set_title: bool,
+    /// Odnośniki jako zwykły tekst, bez sekwencji OSC 8 (dla terminali, które
+    /// wyświetlają je dosłownie)
+    #[arg(long)]
+    no_hyperlinks: bool,
+    /// Kursor widoczny w miejscu pisania podczas animacji linii
+    #[arg(
+        long,
+        value_enum,
+        value_name = "STYL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "block"
+    )]
+    typing_cursor: Option<TypingCursor>,
+    /// Pominięcie baneru startowego
+    #[arg(long)]
+    skip_banner: bool,
+    /// Plik TOML z własnymi regułami klasyfikacji linii (regex → styl)
+    #[arg(long)]
+    rules: Option<PathBuf>,
+    /// Wymuszenie skróconej stopki (domyślnie tylko dla wąskich ramek)
+    #[arg(long)]
+    compact_footer: bool,
+    /// Sekwencje jako zwykłe linie tekstu, bez ramki i numeracji
+    #[arg(long)]
+    compact_mode: bool,
+    /// Zawijanie długich linii na granicach słów zamiast obcinania znakiem ›
+    #[arg(long)]
+    wrap: bool,
+    /// Pola pełnej stopki w wybranej kolejności (ctrl, seq, frame, src)
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        value_name = "POLA",
+        default_values_t = FooterField::all()
+    )]
+    footer_fields: Vec<FooterField>,
+    /// Tło terminala; jasne tło przełącza wbudowane motywy na czytelniejsze odcienie (domyślnie wykrywane z COLORFGBG)
+    #[arg(long, value_enum, value_name = "TŁO")]
+    background: Option<Background>,
+    /// Zwiększenie kontrastu palety (1.0–3.0), np. dla wyblakłego obrazu z projektora
+    #[arg(long, value_name = "WSPÓŁCZYNNIK")]
+    theme_contrast_boost: Option<f32>,
+    /// Wyśrodkowanie sekwencji w pionie w oknie terminala
+    #[arg(long)]
+    vcenter: bool,
+    /// Prezentacja na alternatywnym ekranie terminala, przywracanym po wyjściu
+    #[arg(long)]
+    alt_screen: bool,
+    /// Zachowanie, gdy terminal nie zwróci pozycji kursora
+    #[arg(long, value_enum, value_name = "TRYB", default_value_t = PositionFallback::Top)]
+    probe_position: PositionFallback,
+    /// Numer sekwencji w prawym górnym rogu ramki
+    #[arg(long)]
+    slide_numbers_in_corner: bool,
+    /// Format numeru w rogu ramki ({n} – bieżąca sekwencja, {total} – liczba sekwencji)
+    #[arg(long, value_name = "FORMAT", default_value = "{n}/{total}")]
+    slide_number_format: String,
+    /// Liczba pustych wierszy ramki między kolejnymi wierszami treści
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    line_spacing: usize,
+    /// Liczba dodatkowych pustych wierszy nad nagłówkami
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    heading_spacing: usize,
+    /// Okno (w ms), w którym przytrzymana strzałka przesuwa prezentację tylko o jedną sekwencję (0 wyłącza)
+    #[arg(long, value_name = "MS", default_value_t = 0)]
+    key_repeat_guard: u64,
+    /// Przygaszenie ramki po tylu sekundach bez naciśnięcia klawisza (0 wyłącza)
+    #[arg(long, value_name = "SEKUNDY", default_value_t = 0)]
+    idle_dim: u64,
+    /// Automatyczne przejście do następnej sekwencji po tylu sekundach bez naciśnięcia klawisza (0 wyłącza)
+    #[arg(long, value_name = "SEKUNDY", default_value_t = 0)]
+    auto_advance: u64,
+    /// Zapętlenie prezentacji: po ostatniej sekwencji następuje pierwsza (i odwrotnie)
+    #[arg(long = "loop")]
+    loop_playback: bool,
+    /// Pasek postępu prezentacji pod ramką
+    #[arg(long)]
+    progress: bool,
+    /// Czas na całą prezentację w minutach – pasek postępu czasu w widoku prezentera
+    #[arg(long, value_name = "MINUTY")]
+    total_budget: Option<u64>,
+    /// Widok prezentera: pod stopką odliczanie `@budget`, pasek `--total-budget` i notatki
+    #[arg(long)]
+    presenter: bool,
+    /// Plik lub potok nazwany, do którego trafiają notatki bieżącej sekwencji (np. dla `tail -f`)
+    #[arg(long, value_name = "PLIK")]
+    notes_out: Option<PathBuf>,
+    /// Działanie Enter: następna sekwencja, odsłonięcie bieżącej bez animacji lub nic
+    #[arg(long, value_enum, value_name = "AKCJA", default_value_t = EnterAction::Next)]
+    enter_action: EnterAction,
+    /// Katalog na migawki PNG bieżącej sekwencji, zapisywane klawiszem P
+    #[cfg(feature = "snapshot")]
+    #[arg(long, value_name = "KATALOG")]
+    snapshot_png: Option<PathBuf>,
+    /// Kolorowanie składni w blokach kodu z podanym językiem (także HIGHLIGHT)
+    #[cfg(feature = "syntax")]
+    #[arg(long)]
+    syntax_highlight: bool,
+    /// Format godziny dla znaczników @time i @clock (składnia strftime)
+    #[arg(long)]
+    time_format: Option<String>,
+    /// Polecenie powłoki uruchamiane przy każdej zmianie sekwencji
+    /// (numer i nagłówek w $1/$2 oraz PRESENTATION_SLIDE/PRESENTATION_HEADING)
+    #[arg(long, value_name = "POLECENIE")]
+    on_slide: Option<String>,
+    /// Słowo wyróżniane w całej prezentacji (bez rozróżniania wielkości liter, można powtarzać)
+    #[arg(long, value_name = "SŁOWO")]
+    highlight: Vec<String>,
+    /// Slajd zamykający pokazywany po ostatniej sekwencji (bez pliku: domyślne „FIN”)
+    #[arg(long, value_name = "PLIK", num_args = 0..=1, require_equals = true)]
+    end_slide: Option<Option<PathBuf>>,
+    /// Eksport prezentacji do pliku PDF (wymaga programu wkhtmltopdf)
+    #[arg(long, value_name = "PLIK")]
+    export_pdf: Option<PathBuf>,
+    /// Eksport każdej sekwencji do osobnego pliku SVG we wskazanym katalogu
+    #[arg(long, value_name = "KATALOG", conflicts_with = "export_pdf")]
+    export_svg: Option<PathBuf>,
+    /// Zapis surowych zdarzeń obserwatora plików na stderr (diagnostyka)
+    #[arg(long, hide = true)]
+    trace_events: bool,
+    /// Wypisanie przykładowego skryptu z podaną liczbą linii i zakończenie działania
+    #[arg(long, hide = true, value_name = "N")]
+    generate_demo: Option<usize>,
+    /// Ziarno losowania treści dla --generate-demo
+    #[arg(long, hide = true, default_value_t = 0, requires = "generate_demo")]
+    seed: u64,
+    /// Plik, do którego trafia skrypt z --generate-demo (domyślnie stdout)
+    #[arg(long, hide = true, value_name = "PLIK", requires = "generate_demo")]
+    demo_output: Option<PathBuf>,
+    /// Łączenie kolejnych niepustych linii tekstu w jeden akapit
+    #[arg(long)]
+    reflow: bool,
+    /// Każdy nagłówek rozpoczyna nową sekwencję obejmującą treść aż do kolejnego nagłówka
+    #[arg(long)]
+    split_on_heading: bool,
+    /// Dodaje na początku prezentacji sekwencję ze spisem treści zbudowanym z nagłówków
+    #[arg(long)]
+    toc: bool,
+    /// Przeładowuje prezentację po każdej zmianie plików skryptu
+    #[arg(long)]
+    watch: bool,
+    /// Przeładowuje prezentację w trybie --watch nawet wtedy, gdy treść się nie zmieniła
+    #[arg(long, requires = "watch")]
+    force_reload: bool,
+    /// Kończy obserwację plików po pierwszym udanym przeładowaniu w trybie --watch
+    #[arg(long, requires = "watch")]
+    once: bool,
+    /// Sprawdzenie konfiguracji, skryptu, motywu i baneru bez prezentacji
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+#[clap(rename_all = "kebab_case")]
+pub enum ThemeName {
+    Neon,
+    Amber,
+    Arctic,
+}
+
+impl ThemeName {
+    fn all() -> [ThemeName; 3] {
+        [ThemeName::Neon, ThemeName::Amber, ThemeName::Arctic]
+    }
+
+    fn position(self) -> usize {
+        match self {
+            ThemeName::Neon => 0,
+            ThemeName::Amber => 1,
+            ThemeName::Arctic => 2,
+        }
+    }
+
+    fn next(self) -> Self {
+        let all = Self::all();
+        all[(self.position() + 1) % all.len()]
+    }
+
+    fn prev(self) -> Self {
+        let all = Self::all();
+        all[(self.position() + all.len() - 1) % all.len()]
+    }
+
+    fn defaults(self, background: Background) -> ThemePalette {
+        match (self, background) {
+            (ThemeName::Neon, Background::Dark) => {
+                ThemePalette::new("\x1b[38;5;214m", "\x1b[38;5;238m", "\x1b[38;5;51m")
+                    .with_background(Some("\x1b[48;5;233m".to_string()))
+            }
+            (ThemeName::Neon, Background::Light) => {
+                ThemePalette::new("\x1b[38;5;166m", "\x1b[38;5;245m", "\x1b[38;5;31m")
+                    .with_background(Some("\x1b[48;5;255m".to_string()))
+            }
+            (ThemeName::Amber, Background::Dark) => {
+                ThemePalette::new("\x1b[38;5;178m", "\x1b[38;5;94m", "\x1b[38;5;221m")
+                    .with_background(Some("\x1b[48;5;232m".to_string()))
+            }
+            (ThemeName::Amber, Background::Light) => {
+                ThemePalette::new("\x1b[38;5;130m", "\x1b[38;5;137m", "\x1b[38;5;136m")
+                    .with_background(Some("\x1b[48;5;230m".to_string()))
+            }
+            (ThemeName::Arctic, Background::Dark) => {
+                ThemePalette::new("\x1b[38;5;195m", "\x1b[38;5;250m", "\x1b[38;5;117m")
+                    .with_background(Some("\x1b[48;5;234m".to_string()))
+            }
+            (ThemeName::Arctic, Background::Light) => {
+                ThemePalette::new("\x1b[38;5;25m", "\x1b[38;5;244m", "\x1b[38;5;31m")
+                    .with_background(Some("\x1b[48;5;254m".to_string()))
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
+#[clap(rename_all = "kebab_case")]
+pub enum Background {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Background {
+    fn detect() -> Option<Self> {
+        let colors = env::var("COLORFGBG").ok()?;
+        match colors.rsplit(';').next()?.parse::<u8>().ok()? {
+            7 | 9..=15 => Some(Background::Light),
+            _ => Some(Background::Dark),
+        }
+    }
+}
+
+impl fmt::Display for Background {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Background::Dark => write!(f, "DARK"),
+            Background::Light => write!(f, "LIGHT"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+#[clap(rename_all = "kebab_case")]
+enum JoinMode {
+    Contiguous,
+    Separator,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+#[clap(rename_all = "kebab_case")]
+pub enum FooterField {
+    Ctrl,
+    Seq,
+    Frame,
+    Src,
+}
+
+impl FooterField {
+    fn all() -> [FooterField; 4] {
+        [
+            FooterField::Ctrl,
+            FooterField::Seq,
+            FooterField::Frame,
+            FooterField::Src,
+        ]
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
+#[clap(rename_all = "kebab_case")]
+pub enum PositionFallback {
+    #[default]
+    Top,
+    AltScreen,
+    Scroll,
+}
+
+/// Działanie klawisza `Enter`; tryby przechwytujące tekst powinny go
+/// obsługiwać samodzielnie, zanim trafi do tego mapowania.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
+#[clap(rename_all = "kebab_case")]
+pub enum EnterAction {
+    #[default]
+    Next,
+    Reveal,
+    Noop,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+#[clap(rename_all = "kebab_case")]
+pub enum TypingCursor {
+    Block,
+    Underline,
+}
+
+impl TypingCursor {
+    fn glyph(self) -> char {
+        match self {
+            TypingCursor::Block => '▋',
+            TypingCursor::Underline => '▁',
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
+#[clap(rename_all = "kebab_case")]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+}
+
+impl Easing {
+    fn scale(self, delay: Duration, position: usize, total: usize) -> Duration {
+        if total < 2 {
+            return delay;
+        }
+
+        let progress = position as f64 / (total - 1) as f64;
+        let factor = match self {
+            Easing::Linear => return delay,
+            Easing::EaseIn => 1.75 - 1.5 * progress,
+            Easing::EaseOut => 0.25 + 1.5 * progress,
+        };
+        delay.mul_f64(factor)
+    }
+}
+
+impl fmt::Display for ThemeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ThemeName::Neon => "neon",
+            ThemeName::Amber => "amber",
+            ThemeName::Arctic => "arctic",
+        };
+        write!(f, "{}", name.to_uppercase())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    frame_width: usize,
+    responsive: bool,
+    palette: ThemePalette,
+    theme_path: Option<PathBuf>,
+    watch_theme: bool,
+    banner_path: Option<PathBuf>,
+    banner_width: Option<usize>,
+    sticky_banner: Option<String>,
+    printed_banner: Option<(u16, String)>,
+    presentation_title: String,
+    theme_label: String,
+    builtin_theme: Option<ThemeName>,
+    animations_enabled: bool,
+    typing_cursor: Option<TypingCursor>,
+    easing: Easing,
+    compact_footer: bool,
+    no_color: bool,
+    compact_mode: bool,
+    wrap: bool,
+    deck_info: bool,
+    footer_fields: Vec<FooterField>,
+    corner_number: Option<String>,
+    position_fallback: PositionFallback,
+    line_spacing: usize,
+    heading_spacing: usize,
+    time_format: String,
+    on_slide: Option<String>,
+    highlight: Option<Regex>,
+    trace_events: bool,
+    force_reload: bool,
+    key_repeat_guard: Duration,
+    idle_dim: Duration,
+    auto_advance: Duration,
+    loop_playback: bool,
+    progress_bar: bool,
+    vcenter: bool,
+    alt_screen: bool,
+    set_title: bool,
+    hyperlinks: bool,
+    total_budget: Option<Duration>,
+    presenter_view: bool,
+    enter_action: EnterAction,
+    snapshot_dir: Option<PathBuf>,
+    notes_out: Option<PathBuf>,
+    syntax_highlight: bool,
+    watch_once: bool,
+    banner_gradient: bool,
+    banner_color_cycle: bool,
+    upcase_headings: bool,
+    replay: Vec<Duration>,
+    pause_at: Vec<usize>,
+    background: Background,
+    contrast_boost: Option<f32>,
+    heading_banner: bool,
+}
+
+impl Config {
+    fn from_sources(cli: &Cli) -> Result<Self, Box<dyn std::error::Error>> {
+        let background = cli
+            .background
+            .or_else(Background::detect)
+            .unwrap_or_default();
+        let builder = if let Some(path) = cli.theme_path.as_deref() {
+            let spec = theme::load_from_path(path)?;
+            Config::builder().theme(spec.label(), resolve_palette(spec.palette()))
+        } else if let Some((label, palette)) = image_theme(cli)? {
+            Config::builder().theme(label, resolve_palette(&palette))
+        } else {
+            let theme = cli
+                .theme
+                .or_else(|| {
+                    env::var("PRESENTATION_THEME")
+                        .ok()
+                        .and_then(|value| ThemeName::from_str(&value, true).ok())
+                })
+                .unwrap_or(ThemeName::Neon);
+
+            Config::builder()
+                .background(background)
+                .builtin_theme(theme)
+                .palette(resolve_palette(&theme.defaults(background)))
+        };
+
+        let frame_width = cli
+            .frame_width
+            .or_else(|| {
+                env::var("FRAME_WIDTH")
+                    .ok()
+                    .and_then(|value| value.parse().ok())
+            })
+            .unwrap_or_else(|| {
+                initial_frame_width(crossterm::terminal::size().ok().map(|(columns, _)| columns))
+            });
+
+        let presentation_title = cli
+            .title
+            .clone()
+            .or_else(|| env::var("PRESENTATION_TITLE").ok())
+            .unwrap_or_else(|| "Rust Lab Terminal".to_string());
+
+        let default_banner = env::var("DEFAULT_BANNER_PATH")
+            .unwrap_or_else(|_| "presentations/banner.txt".to_string());
+        let banner_path = if cli.skip_banner {
+            None
+        } else {
+            Some(
+                cli.banner
+                    .clone()
+                    .unwrap_or_else(|| PathBuf::from(default_banner)),
+            )
+        };
+
+        let time_format = cli
+            .time_format
+            .clone()
+            .or_else(|| env::var("TIME_FORMAT").ok())
+            .unwrap_or_else(|| "%H:%M:%S".to_string());
+
+        builder
+            .frame_width(frame_width)
+            .responsive(cli.responsive)
+            .contrast_boost(cli.theme_contrast_boost)
+            .theme_path(cli.theme_path.clone(), cli.watch_theme)
+            .banner(banner_path, cli.sticky_banner)
+            .banner_gradient(cli.banner_gradient)
+            .banner_color_cycle(cli.animate_banner_color_cycle)
+            .upcase_headings(!cli.no_upcase_headings)
+            .heading_banner(cli.heading_banner)
+            .replay(cli.replay.clone())
+            .pause_at(cli.pause_at.clone())
+            .banner_width(cli.banner_width.or_else(|| {
+                env::var("BANNER_WIDTH")
+                    .ok()
+                    .and_then(|value| value.parse().ok())
+            }))
+            .title(presentation_title)
+            .animations(!cli.instant)
+            .no_color(
+                cli.no_color || env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()),
+            )
+            .typing_cursor(cli.typing_cursor)
+            .easing(
+                cli.ease
+                    .or_else(|| {
+                        env::var("EASE")
+                            .ok()
+                            .and_then(|value| Easing::from_str(&value, true).ok())
+                    })
+                    .unwrap_or_default(),
+            )
+            .compact_footer(cli.compact_footer)
+            .compact_mode(cli.compact_mode)
+            .wrap(cli.wrap || env::var_os("WRAP").is_some())
+            .deck_info(cli.deck_info)
+            .footer_fields(cli.footer_fields.clone())
+            .corner_number(
+                cli.slide_numbers_in_corner
+                    .then(|| cli.slide_number_format.clone()),
+            )
+            .position_fallback(cli.probe_position)
+            .spacing(cli.line_spacing, cli.heading_spacing)
+            .time_format(time_format)
+            .on_slide(cli.on_slide.clone().or_else(|| env::var("ON_SLIDE").ok()))
+            .highlight(cli.highlight.clone())
+            .trace_events(cli.trace_events || env::var_os("TRACE_EVENTS").is_some())
+            .force_reload(cli.force_reload)
+            .watch_once(cli.once)
+            .key_repeat_guard(Duration::from_millis(cli.key_repeat_guard))
+            .idle_dim(Duration::from_secs(cli.idle_dim))
+            .auto_advance(Duration::from_secs(cli.auto_advance))
+            .loop_playback(cli.loop_playback)
+            .progress_bar(cli.progress)
+            .vcenter(cli.vcenter)
+            .alt_screen(cli.alt_screen)
+            .set_title(!cli.no_set_title)
+            .hyperlinks(!cli.no_hyperlinks)
+            .total_budget(
+                cli.total_budget
+                    .filter(|minutes| *minutes > 0)
+                    .map(|minutes| Duration::from_secs(minutes * 60)),
+            )
+            .presenter_view(cli.presenter)
+            .enter_action(cli.enter_action)
+            .snapshot_dir(snapshot_dir(cli))
+            .notes_out(cli.notes_out.clone())
+            .syntax_highlight(syntax_highlight(cli))
+            .build()
+    }
+
+    pub(crate) fn frame_width(&self) -> usize {
+        self.frame_width
+    }
+
+    pub(crate) fn color_accent(&self) -> &str {
+        if self.no_color {
+            ""
+        } else {
+            self.palette.accent()
+        }
+    }
+
+    pub(crate) fn color_dim(&self) -> &str {
+        if self.no_color {
+            ""
+        } else {
+            self.palette.dim()
+        }
+    }
+
+    pub(crate) fn color_glow(&self) -> &str {
+        if self.no_color {
+            ""
+        } else {
+            self.palette.glow()
+        }
+    }
+
+    pub(crate) fn color_background(&self) -> &str {
+        if self.no_color {
+            ""
+        } else {
+            self.palette.background().unwrap_or("")
+        }
+    }
+
+    /// Kod stylu ANSI (`RESET`, `BOLD`…) albo pusty napis przy `--no-color`.
+    /// Przy tle motywu `RESET` nie zdejmuje tła – robi to dopiero
+    /// [`Config::row_end`] na końcu wiersza ramki.
+    pub(crate) fn style(&self, code: &'static str) -> &'static str {
+        if self.no_color {
+            ""
+        } else if code == RESET && !self.color_background().is_empty() {
+            RESET_KEEP_BACKGROUND
+        } else {
+            code
+        }
+    }
+
+    /// Pełny reset kończący wiersz ramki, razem z tłem motywu.
+    pub(crate) fn row_end(&self) -> &'static str {
+        if self.no_color { "" } else { RESET }
+    }
+
+    pub(crate) fn color(&self, slot: ColorSlot) -> &str {
+        match slot {
+            ColorSlot::Accent => self.color_accent(),
+            ColorSlot::Dim => self.color_dim(),
+            ColorSlot::Glow => self.color_glow(),
+        }
+    }
+
+    pub(crate) fn glyphs(&self) -> &ThemeGlyphs {
+        self.palette.glyphs()
+    }
+
+    fn gutter_number_color(&self) -> &str {
+        self.color(self.palette.gutter_number())
+    }
+
+    fn gutter_separator_color(&self) -> &str {
+        self.color(self.palette.gutter_separator())
+    }
+
+    pub(crate) fn watched_theme_path(&self) -> Option<&Path> {
+        self.theme_path.as_deref().filter(|_| self.watch_theme)
+    }
+
+    pub(crate) fn reload_theme(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(path) = self.theme_path.as_deref() else {
+            return Ok(());
+        };
+        let spec = theme::load_from_path(path)?;
+        self.palette = self.boosted(resolve_palette(spec.palette()));
+        self.theme_label = spec.label().to_string();
+        Ok(())
+    }
+
+    pub(crate) fn themed(&self, theme: &SlideTheme) -> Config {
+        let mut themed = self.clone();
+        match theme {
+            SlideTheme::Builtin(theme) => {
+                themed.palette = self.boosted(theme.defaults(self.background));
+                themed.theme_label = theme.to_string();
+            }
+            SlideTheme::File { label, palette } => {
+                themed.palette = self.boosted(ThemePalette::clone(palette));
+                themed.theme_label = label.clone();
+            }
+        }
+        themed
+    }
+
+    pub(crate) fn cycle_theme(&mut self, forward: bool) -> bool {
+        let Some(current) = self.builtin_theme else {
+            return false;
+        };
+        let theme = if forward {
+            current.next()
+        } else {
+            current.prev()
+        };
+        self.palette = self.boosted(theme.defaults(self.background));
+        self.theme_label = theme.to_string();
+        self.builtin_theme = Some(theme);
+        true
+    }
+
+    pub(crate) fn banner_path(&self) -> Option<&Path> {
+        self.banner_path.as_deref()
+    }
+
+    /// Pusty plik (zapis w toku) zostawia poprzedni baner, jak brak pliku.
+    pub(crate) fn reload_banner(&mut self) -> io::Result<()> {
+        let Some(path) = self.banner_path.as_deref() else {
+            return Ok(());
+        };
+        let banner = load_banner(path)?;
+        if banner.trim().is_empty() {
+            return Err(io::Error::other("pusty plik banera"));
+        }
+        self.sticky_banner = Some(banner);
+        Ok(())
+    }
+
+    pub(crate) fn sticky_banner(&self) -> Option<&str> {
+        self.sticky_banner.as_deref()
+    }
+
+    /// Baner startowy wypisany nad prezentacją: wiersz jego początku i treść,
+    /// dopóki `--animate-banner-color-cycle` może go przemalowywać.
+    pub(crate) fn printed_banner(&self) -> Option<(u16, &str)> {
+        self.printed_banner
+            .as_ref()
+            .map(|(top, banner)| (*top, banner.as_str()))
+    }
+
+    pub(crate) fn set_printed_banner(&mut self, printed: Option<(u16, String)>) {
+        self.printed_banner = printed;
+    }
+
+    fn banner_width(&self) -> usize {
+        self.banner_width
+            .or_else(|| {
+                crossterm::terminal::size()
+                    .ok()
+                    .map(|(columns, _)| usize::from(columns))
+            })
+            .unwrap_or(self.frame_width)
+    }
+
+    pub(crate) fn presentation_title(&self) -> &str {
+        &self.presentation_title
+    }
+
+    pub(crate) fn theme_label(&self) -> &str {
+        &self.theme_label
+    }
+
+    pub(crate) fn animations_enabled(&self) -> bool {
+        self.animations_enabled
+    }
+
+    fn typing_cursor(&self) -> Option<TypingCursor> {
+        self.typing_cursor
+    }
+
+    pub(crate) fn compact_footer(&self) -> bool {
+        self.compact_footer
+    }
+
+    fn compact_mode(&self) -> bool {
+        self.compact_mode
+    }
+
+    fn wrap(&self) -> bool {
+        self.wrap
+    }
+
+    fn deck_info(&self) -> bool {
+        self.deck_info
+    }
+
+    pub(crate) fn footer_fields(&self) -> &[FooterField] {
+        &self.footer_fields
+    }
+
+    pub(crate) fn corner_number(&self, index: usize, total: usize) -> Option<String> {
+        self.corner_number.as_ref().map(|format| {
+            format
+                .replace("{n}", &(index + 1).to_string())
+                .replace("{total}", &total.to_string())
+        })
+    }
+
+    pub(crate) fn position_fallback(&self) -> PositionFallback {
+        self.position_fallback
+    }
+
+    pub(crate) fn line_spacing(&self) -> usize {
+        self.line_spacing
+    }
+
+    pub(crate) fn heading_spacing(&self) -> usize {
+        self.heading_spacing
+    }
+
+    pub(crate) fn time_format(&self) -> &str {
+        &self.time_format
+    }
+
+    fn highlight(&self) -> Option<&Regex> {
+        self.highlight.as_ref()
+    }
+
+    pub(crate) fn trace_events(&self) -> bool {
+        self.trace_events
+    }
+
+    pub(crate) fn force_reload(&self) -> bool {
+        self.force_reload
+    }
+
+    pub(crate) fn key_repeat_guard(&self) -> Duration {
+        self.key_repeat_guard
+    }
+
+    pub(crate) fn idle_dim(&self) -> Option<Duration> {
+        Some(self.idle_dim).filter(|idle| !idle.is_zero())
+    }
+
+    pub(crate) fn auto_advance(&self) -> Option<Duration> {
+        Some(self.auto_advance).filter(|every| !every.is_zero())
+    }
+
+    pub(crate) fn loop_playback(&self) -> bool {
+        self.loop_playback
+    }
+
+    pub(crate) fn progress_bar(&self) -> bool {
+        self.progress_bar
+    }
+
+    pub(crate) fn vcenter(&self) -> bool {
+        self.vcenter
+    }
+
+    pub(crate) fn alt_screen(&self) -> bool {
+        self.alt_screen
+    }
+
+    pub(crate) fn no_color(&self) -> bool {
+        self.no_color
+    }
+
+    /// Tytuł okna to także sekwencja sterująca, więc `--no-color` go wyłącza.
+    pub(crate) fn set_title(&self) -> bool {
+        self.set_title && !self.no_color
+    }
+
+    /// Odnośniki OSC 8 znikają przy `--no-color`, jak pozostałe sekwencje.
+    pub(crate) fn hyperlinks(&self) -> bool {
+        self.hyperlinks && !self.no_color
+    }
+
+    pub(crate) fn total_budget(&self) -> Option<Duration> {
+        self.total_budget
+    }
+
+    pub(crate) fn presenter_view(&self) -> bool {
+        self.presenter_view
+    }
+
+    pub(crate) fn enter_action(&self) -> EnterAction {
+        self.enter_action
+    }
+
+    pub(crate) fn snapshot_dir(&self) -> Option<&Path> {
+        self.snapshot_dir.as_deref()
+    }
+
+    pub(crate) fn notes_out(&self) -> Option<&Path> {
+        self.notes_out.as_deref()
+    }
+
+    pub(crate) fn syntax_highlight(&self) -> bool {
+        self.syntax_highlight && !self.no_color
+    }
+
+    /// Kopia z kolorami motywu mimo `--no-color` – pliki eksportu nie są
+    /// wyjściem terminala.
+    pub(crate) fn with_colors(&self) -> Config {
+        let mut colored = self.clone();
+        colored.no_color = false;
+        colored
+    }
+
+    pub(crate) fn dimmed(&self) -> Config {
+        let mut dimmed = self.clone();
+        dimmed.palette = dimmed.palette.dimmed();
+        dimmed
+    }
+
+    pub(crate) fn watch_once(&self) -> bool {
+        self.watch_once
+    }
+
+    fn banner_gradient(&self) -> bool {
+        self.banner_gradient
+    }
+
+    pub(crate) fn banner_color_cycle(&self) -> bool {
+        self.banner_color_cycle && self.animations_enabled
+    }
+
+    fn heading_banner(&self) -> bool {
+        self.heading_banner
+    }
+
+    fn boosted(&self, palette: ThemePalette) -> ThemePalette {
+        match self.contrast_boost {
+            Some(factor) => palette.with_contrast(factor),
+            None => palette,
+        }
+    }
+
+    fn background(&self) -> Background {
+        self.background
+    }
+
+    pub(crate) fn replay_dwell(&self, index: usize) -> Option<Duration> {
+        self.replay.get(index).copied()
+    }
+
+    /// Sekwencja z `--pause-at`: ani `--replay`, ani `--auto-advance` jej nie
+    /// przewijają.
+    pub(crate) fn paused(&self, index: usize) -> bool {
+        self.pause_at.contains(&(index + 1))
+    }
+
+    fn validate_pause_at(&self, total: usize) -> Result<(), String> {
+        match self
+            .pause_at
+            .iter()
+            .find(|number| !(1..=total).contains(*number))
+        {
+            Some(number) => Err(format!(
+                "Sekwencja {} z --pause-at nie istnieje (prezentacja ma {} sekwencji)",
+                number, total
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn upcase(&self, text: &str) -> String {
+        if self.upcase_headings {
+            span::uppercase(text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    pub(crate) fn on_slide(&self) -> Option<&str> {
+        self.on_slide.as_deref()
+    }
+
+    pub(crate) fn pause(&self, duration: Duration) {
+        if self.animations_enabled {
+            thread::sleep(duration);
+        }
+    }
+
+    pub(crate) fn typing_delay(&self, delay: Duration, position: usize, total: usize) -> Duration {
+        self.easing.scale(delay, position, total)
+    }
+
+    /// Ręczna zmiana szerokości wyłącza dopasowanie `--responsive` do końca
+    /// działania programu.
+    pub(crate) fn adjust_frame_width(&mut self, delta: isize) -> bool {
+        let current = self.frame_width as isize;
+        let updated = (current + delta).max(MIN_FRAME_WIDTH as isize) as usize;
+        if updated != self.frame_width {
+            self.frame_width = updated;
+            self.responsive = false;
+            return true;
+        }
+        false
+    }
+
+    pub(crate) fn fit_to_terminal(&mut self, columns: u16) {
+        if self.responsive {
+            self.frame_width = usize::from(columns).max(MIN_FRAME_WIDTH);
+        }
+    }
+}
+
+/// Szerokość ramki bez `--frame-width` i `FRAME_WIDTH`: szerokość terminala
+/// przycięta do 40–120 kolumn, a bez terminala (lub przy zerowej szerokości)
+/// domyślne 120.
+fn initial_frame_width(terminal_columns: Option<u16>) -> usize {
+    terminal_columns
+        .filter(|columns| *columns > 0)
+        .map_or(DEFAULT_FRAME_WIDTH, |columns| {
+            usize::from(columns).clamp(MIN_FRAME_WIDTH, DEFAULT_FRAME_WIDTH)
+        })
+}
+
+fn resolve_palette(defaults: &ThemePalette) -> ThemePalette {
+    ThemePalette::new(
+        env::var("COLOR_ACCENT").unwrap_or_else(|_| defaults.accent().to_string()),
+        env::var("COLOR_DIM").unwrap_or_else(|_| defaults.dim().to_string()),
+        env::var("COLOR_GLOW").unwrap_or_else(|_| defaults.glow().to_string()),
+    )
+    .with_background(
+        env::var("COLOR_BACKGROUND")
+            .ok()
+            .or_else(|| defaults.background().map(str::to_string)),
+    )
+    .with_gutter(
+        env_color_slot("GUTTER_NUMBER").unwrap_or(defaults.gutter_number()),
+        env_color_slot("GUTTER_SEPARATOR").unwrap_or(defaults.gutter_separator()),
+    )
+    .with_glyphs(defaults.glyphs().clone())
+}
+
+#[cfg(feature = "image")]
+fn image_theme(cli: &Cli) -> Result<Option<(String, ThemePalette)>, Box<dyn std::error::Error>> {
+    let Some(path) = cli.palette_from_image.as_deref() else {
+        return Ok(None);
+    };
+
+    match image_palette::derive(path)? {
+        Some(derived) => {
+            println!(
+                "PALETTE :: accent {}  dim {}  glow {}",
+                derived.accent.hex(),
+                derived.dim.hex(),
+                derived.glow.hex()
+            );
+            let label = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "image".to_string());
+            Ok(Some((label, derived.palette())))
+        }
+        None => {
+            eprintln!(
+                "⚠ Obraz {} nie ma wyraźnych kolorów – używam motywu wbudowanego",
+                path.display()
+            );
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(not(feature = "image"))]
+fn image_theme(_cli: &Cli) -> Result<Option<(String, ThemePalette)>, Box<dyn std::error::Error>> {
+    Ok(None)
+}
+
+#[cfg(feature = "snapshot")]
+fn snapshot_dir(cli: &Cli) -> Option<PathBuf> {
+    cli.snapshot_png.clone()
+}
+
+#[cfg(not(feature = "snapshot"))]
+fn snapshot_dir(_cli: &Cli) -> Option<PathBuf> {
+    None
+}
+
+#[cfg(feature = "syntax")]
+fn syntax_highlight(cli: &Cli) -> bool {
+    cli.syntax_highlight || env::var_os("HIGHLIGHT").is_some()
+}
+
+#[cfg(not(feature = "syntax"))]
+fn syntax_highlight(_cli: &Cli) -> bool {
+    false
+}
+
+#[cfg(feature = "syntax")]
+fn highlight_code(
+    config: &Config,
+    language: &str,
+    lines: &[String],
+    available: usize,
+) -> Option<Vec<String>> {
+    syntax::highlight(config, language, lines, available)
+}
+
+#[cfg(not(feature = "syntax"))]
+fn highlight_code(
+    _config: &Config,
+    _language: &str,
+    _lines: &[String],
+    _available: usize,
+) -> Option<Vec<String>> {
+    None
+}
+
+#[cfg(feature = "snapshot")]
+pub(crate) fn save_snapshot(
+    config: &Config,
+    slides: &[Slide],
+    index: usize,
+    directory: &Path,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    snapshot::write_png(config, slides, index, directory)
+}
+
+#[cfg(not(feature = "snapshot"))]
+pub(crate) fn save_snapshot(
+    _config: &Config,
+    _slides: &[Slide],
+    _index: usize,
+    _directory: &Path,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    Err("Migawki PNG wymagają kompilacji z funkcją snapshot".into())
+}
+
+fn env_color_slot(name: &str) -> Option<ColorSlot> {
+    env::var(name)
+        .ok()
+        .and_then(|value| ColorSlot::from_str(&value, true).ok())
+}
+
+fn highlight_pattern(words: &[String]) -> Result<Option<Regex>, regex::Error> {
+    let alternatives: Vec<String> = words
+        .iter()
+        .map(|word| word.trim())
+        .filter(|word| !word.is_empty())
+        .map(regex::escape)
+        .collect();
+    if alternatives.is_empty() {
+        return Ok(None);
+    }
+    Regex::new(&format!("(?i){}", alternatives.join("|"))).map(Some)
+}
+
+/// Motyw z dyrektywy `@theme`, rozwiązany raz przy wczytaniu talii.
+#[derive(Debug, Clone)]
+pub(crate) enum SlideTheme {
+    Builtin(ThemeName),
+    File {
+        label: String,
+        palette: Box<ThemePalette>,
+    },
+}
+
+impl SlideTheme {
+    /// Ścieżka do pliku TOML jest liczona od katalogu pliku sekwencji.
+    pub(crate) fn resolve(name: &str, source: &Path) -> Result<Self, String> {
+        if let Ok(theme) = ThemeName::from_str(name, true) {
+            return Ok(Self::Builtin(theme));
+        }
+        let path = source.parent().unwrap_or(Path::new("")).join(name);
+        if !path.is_file() {
+            return Err(format!("Nieznany motyw sekwencji „{}”", name));
+        }
+        let spec = theme::load_from_path(&path).map_err(|error| {
+            format!("Motyw sekwencji ({}) nie został wczytany: {}", name, error)
+        })?;
+        Ok(Self::File {
+            label: spec.label().to_string(),
+            palette: Box::new(resolve_palette(spec.palette())),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+pub(crate) struct Segment {
+    kind: SegmentKind,
+    frame_title: Option<String>,
+    theme: Option<String>,
+    instant: bool,
+    banner_heading: bool,
+    heading_level: usize,
+    budget: Option<Duration>,
+    align: Align,
+}
+
+/// Położenie tekstu segmentu w ramce; przy nieparzystej liczbie wolnych
+/// kolumn wyśrodkowanie zostawia nadmiarową spację po prawej.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub(crate) enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Hash)]
+pub(crate) enum SegmentKind {
+    Heading(Vec<Span>),
+    Bullet {
+        indent: usize,
+        text: Vec<Span>,
+    },
+    Callout(Vec<Span>),
+    Quote(Vec<Vec<Span>>),
+    Table {
+        headers: Vec<Vec<Span>>,
+        rows: Vec<Vec<Vec<Span>>>,
+    },
+    Contents {
+        title: Vec<Span>,
+        slide: usize,
+    },
+    Plain(Vec<Span>),
+    Custom {
+        style: RuleStyle,
+        text: Vec<Span>,
+    },
+    Task {
+        done: bool,
+        text: Vec<Span>,
+    },
+    Numbered {
+        number: usize,
+        text: Vec<Span>,
+    },
+    Separator,
+    SlideBreak,
+    Spacer(usize),
+    Code {
+        language: Option<String>,
+        lines: Vec<String>,
+    },
+    Notes(Vec<String>),
+}
+
+impl Segment {
+    fn new(kind: SegmentKind) -> Self {
+        Self {
+            kind,
+            frame_title: None,
+            theme: None,
+            instant: false,
+            banner_heading: false,
+            heading_level: 1,
+            budget: None,
+            align: Align::Left,
+        }
+    }
+
+    fn with_frame_title(mut self, frame_title: Option<String>) -> Self {
+        self.frame_title = frame_title;
+        self
+    }
+
+    pub(crate) fn frame_title(&self) -> Option<&str> {
+        self.frame_title.as_deref()
+    }
+
+    fn with_theme(mut self, theme: Option<String>) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub(crate) fn theme(&self) -> Option<&str> {
+        self.theme.as_deref()
+    }
+
+    fn with_instant(mut self, instant: bool) -> Self {
+        self.instant = instant;
+        self
+    }
+
+    fn instant(&self) -> bool {
+        self.instant
+    }
+
+    fn with_banner_heading(mut self, banner_heading: bool) -> Self {
+        self.banner_heading = banner_heading;
+        self
+    }
+
+    fn banner_heading(&self) -> bool {
+        self.banner_heading
+    }
+
+    fn with_heading_level(mut self, level: usize) -> Self {
+        self.heading_level = level;
+        self
+    }
+
+    /// Nagłówek `#` – tylko on zaczyna nową sekwencję przy `--split-on-heading`.
+    fn top_level_heading(&self) -> bool {
+        matches!(self.kind, SegmentKind::Heading(_)) && self.heading_level == 1
+    }
+
+    fn with_budget(mut self, budget: Option<Duration>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    pub(crate) fn budget(&self) -> Option<Duration> {
+        self.budget
+    }
+
+    fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    fn align(&self) -> Align {
+        self.align
+    }
+
+    pub(crate) fn kind(&self) -> &SegmentKind {
+        &self.kind
+    }
+
+    fn spans(&self) -> Option<&[Span]> {
+        match &self.kind {
+            SegmentKind::Heading(spans)
+            | SegmentKind::Bullet { text: spans, .. }
+            | SegmentKind::Callout(spans)
+            | SegmentKind::Plain(spans)
+            | SegmentKind::Custom { text: spans, .. }
+            | SegmentKind::Task { text: spans, .. }
+            | SegmentKind::Numbered { text: spans, .. }
+            | SegmentKind::Contents { title: spans, .. } => Some(spans),
+            SegmentKind::Separator
+            | SegmentKind::SlideBreak
+            | SegmentKind::Spacer(_)
+            | SegmentKind::Code { .. }
+            | SegmentKind::Quote(_)
+            | SegmentKind::Table { .. }
+            | SegmentKind::Notes(_) => None,
+        }
+    }
+
+    pub(crate) fn heading(&self) -> Option<String> {
+        match &self.kind {
+            SegmentKind::Heading(spans) => Some(plain_text(spans)),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn mentions_clock(&self) -> bool {
+        match &self.kind {
+            SegmentKind::Quote(rows) => rows
+                .iter()
+                .any(|spans| clock::mentions_clock(&plain_text(spans))),
+            _ => self
+                .spans()
+                .is_some_and(|spans| clock::mentions_clock(&plain_text(spans))),
+        }
+    }
+}
+
+pub(crate) struct SegmentParser<'a, R> {
+    lines: io::Lines<R>,
+    rules: &'a RuleSet,
+    frame_title: Option<String>,
+    theme: Option<String>,
+    pending_instant: bool,
+    pending_banner: bool,
+    pending_break: bool,
+    budget: Option<Duration>,
+}
+
+impl<'a, R: BufRead> SegmentParser<'a, R> {
+    pub(crate) fn new(reader: R, rules: &'a RuleSet) -> Self {
+        Self {
+            lines: reader.lines(),
+            rules,
+            frame_title: None,
+            theme: None,
+            pending_instant: false,
+            pending_banner: false,
+            pending_break: false,
+            budget: None,
+        }
+    }
+
+    fn next_line(&mut self) -> Option<io::Result<String>> {
+        let mut line = match self.lines.next()? {
+            Ok(line) => line,
+            Err(error) => return Some(Err(error)),
+        };
+        while let Some(head) = continued(&line) {
+            match self.lines.next() {
+                Some(Ok(next)) => line = format!("{} {}", head.trim_end(), next.trim_start()),
+                Some(Err(error)) => return Some(Err(error)),
+                None => break,
+            }
+        }
+        if line.ends_with("\\\\") {
+            line.pop();
+        }
+        Some(Ok(line))
+    }
+
+    /// Linie aż do zamykającego płotka (lub końca pliku) trafiają do bloku
+    /// bez zmian – bez łączenia ukośnikiem i bez dyrektyw.
+    fn code_block(&mut self, language: Option<String>) -> io::Result<Segment> {
+        let mut lines = Vec::new();
+        for line in self.lines.by_ref() {
+            let line = line?;
+            if is_closing_fence(&line) {
+                break;
+            }
+            lines.push(line.trim_end().replace('\t', CODE_TAB));
+        }
+        Ok(Segment::new(SegmentKind::Code { language, lines }))
+    }
+
+    /// Linie po znaczniku `???` aż do końca slajdu (`---` lub końca pliku)
+    /// to notatki prelegenta – bez dyrektyw i bez łączenia ukośnikiem; puste
+    /// linie są pomijane.
+    fn notes(&mut self) -> io::Result<Segment> {
+        let mut notes = Vec::new();
+        for line in self.lines.by_ref() {
+            let line = line?;
+            if is_slide_break(&line) {
+                self.pending_break = true;
+                break;
+            }
+            let note = line.trim();
+            if !note.is_empty() {
+                notes.push(note.to_string());
+            }
+        }
+        Ok(Segment::new(SegmentKind::Notes(notes)))
+    }
+}
+
+fn continued(line: &str) -> Option<&str> {
+    line.strip_suffix('\\').filter(|head| !head.ends_with('\\'))
+}
+
+impl<R: BufRead> Iterator for SegmentParser<'_, R> {
+    type Item = io::Result<Segment>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if std::mem::take(&mut self.pending_break) {
+            return Some(Ok(Segment::new(SegmentKind::SlideBreak)));
+        }
+        loop {
+            let line = match self.next_line()? {
+                Ok(line) => line,
+                Err(error) => return Some(Err(error)),
+            };
+            if let Some(title) = parse_directive(&line, FRAME_TITLE_DIRECTIVE) {
+                self.frame_title = Some(title.to_string()).filter(|title| !title.is_empty());
+                continue;
+            }
+            if let Some(theme) = parse_directive(&line, THEME_DIRECTIVE) {
+                self.theme = Some(theme.to_string()).filter(|theme| !theme.is_empty());
+                continue;
+            }
+            if let Some(budget) = parse_budget(&line) {
+                self.budget = Some(budget);
+                continue;
+            }
+            if parse_directive(&line, BANNER_HEADING_DIRECTIVE) == Some("") {
+                self.pending_banner = true;
+                continue;
+            }
+            let (text, instant) = match parse_directive(&line, INSTANT_DIRECTIVE) {
+                Some("") => {
+                    self.pending_instant = true;
+                    continue;
+                }
+                Some(rest) => (rest, true),
+                None => (line.as_str(), false),
+            };
+            let segment = if text.trim() == NOTES_MARKER {
+                match self.notes() {
+                    Ok(segment) => segment,
+                    Err(error) => return Some(Err(error)),
+                }
+            } else if let Some(language) = parse_fence(text) {
+                match self.code_block(language) {
+                    Ok(segment) => segment,
+                    Err(error) => return Some(Err(error)),
+                }
+            } else {
+                match parse_spacer(text) {
+                    Some(rows) => Segment::new(SegmentKind::Spacer(rows)),
+                    None => classify_segment(text, self.rules),
+                }
+            };
+            return Some(Ok(segment
+                .with_frame_title(self.frame_title.take())
+                .with_theme(self.theme.take())
+                .with_instant(instant || std::mem::take(&mut self.pending_instant))
+                .with_banner_heading(std::mem::take(&mut self.pending_banner))
+                .with_budget(self.budget.take())));
+        }
+    }
+}
+
+fn parse_segments<R: BufRead>(reader: R, rules: &RuleSet) -> io::Result<Vec<Segment>> {
+    SegmentParser::new(reader, rules).collect()
+}
+
+fn reflow_paragraphs(segments: Vec<Segment>) -> Vec<Segment> {
+    let mut reflowed: Vec<Segment> = Vec::with_capacity(segments.len());
+    for segment in segments {
+        if let SegmentKind::Plain(spans) = &segment.kind
+            && !spans.is_empty()
+            && segment.frame_title.is_none()
+            && segment.theme.is_none()
+            && segment.budget.is_none()
+            && let Some(previous) = reflowed.last_mut()
+            && let SegmentKind::Plain(previous_spans) = &mut previous.kind
+            && !previous_spans.is_empty()
+        {
+            previous_spans.push(Span::plain(" "));
+            previous_spans.extend(spans.iter().cloned());
+            previous.instant |= segment.instant;
+            continue;
+        }
+        reflowed.push(segment);
+    }
+    reflowed
+}
+
+/// Sąsiednie linie `>` łączą się w jeden cytat z wierszem na linię; pusta
+/// linia `>` zostaje pustym wierszem cytatu. Dyrektywy ramki, motywu
+/// i budżetu przy kolejnej linii rozpoczynają nowy cytat, jak przy `--reflow`.
+fn merge_quotes(segments: Vec<Segment>) -> Vec<Segment> {
+    let mut merged: Vec<Segment> = Vec::with_capacity(segments.len());
+    for segment in segments {
+        if let SegmentKind::Callout(spans) = &segment.kind
+            && segment.frame_title.is_none()
+            && segment.theme.is_none()
+            && segment.budget.is_none()
+            && let Some(previous) = merged.last_mut()
+        {
+            let rows = match &mut previous.kind {
+                SegmentKind::Callout(first) => Some(vec![std::mem::take(first)]),
+                SegmentKind::Quote(rows) => Some(std::mem::take(rows)),
+                _ => None,
+            };
+            if let Some(mut rows) = rows {
+                rows.push(spans.clone());
+                previous.kind = SegmentKind::Quote(rows);
+                previous.instant |= segment.instant;
+                continue;
+            }
+        }
+        merged.push(segment);
+    }
+    merged
+}
+
+/// Sąsiednie wiersze `| a | b |` łączą się w jedną tabelę: pierwszy jest
+/// nagłówkiem, a wiersze oddzielające `|---|` są pomijane. Dyrektywy przy
+/// kolejnym wierszu rozpoczynają nową tabelę, jak przy cytatach.
+fn merge_tables(segments: Vec<Segment>) -> Vec<Segment> {
+    let mut merged: Vec<Segment> = Vec::with_capacity(segments.len());
+    for segment in segments {
+        if let SegmentKind::Table { headers: cells, .. } = &segment.kind
+            && segment.frame_title.is_none()
+            && segment.theme.is_none()
+            && segment.budget.is_none()
+            && let Some(previous) = merged.last_mut()
+            && let SegmentKind::Table { rows, .. } = &mut previous.kind
+        {
+            if !table::is_separator(cells) {
+                rows.push(cells.clone());
+            }
+            previous.instant |= segment.instant;
+            continue;
+        }
+        merged.push(segment);
+    }
+    merged
+}
+
+fn parse_directive<'a>(line: &'a str, directive: &str) -> Option<&'a str> {
+    let rest = line.trim().strip_prefix(directive)?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(rest.trim())
+}
+
+fn parse_spacer(line: &str) -> Option<usize> {
+    match parse_directive(line, SPACE_DIRECTIVE)? {
+        "" => Some(1),
+        count => count
+            .parse::<usize>()
+            .ok()
+            .map(|rows| rows.min(MAX_SPACER_ROWS)),
+    }
+}
+
+/// Czas na slajd z `@budget 90s` (także `2m` lub samej liczby sekund);
+/// niepoprawna wartość zostawia linię jako zwykły tekst, jak przy `@space`.
+fn parse_budget(line: &str) -> Option<Duration> {
+    let value = parse_directive(line, BUDGET_DIRECTIVE)?;
+    let (number, unit) = match value.strip_suffix('m') {
+        Some(minutes) => (minutes, 60),
+        None => (value.strip_suffix('s').unwrap_or(value), 1),
+    };
+    let number: u64 = number.parse().ok().filter(|number| *number > 0)?;
+    Some(Duration::from_secs(number * unit))
+}
+
+fn parse_fence(line: &str) -> Option<Option<String>> {
+    let info = line
+        .trim()
+        .strip_prefix(CODE_FENCE)?
+        .trim_start_matches('`');
+    Some(info.split_whitespace().next().map(str::to_string))
+}
+
+fn is_closing_fence(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.len() >= CODE_FENCE.len() && trimmed.chars().all(|ch| ch == '`')
+}
+
+/// Punkt listy numerowanej `1. tekst` (także `1) tekst`): numer i treść.
+fn parse_numbered(line: &str) -> Option<(usize, &str)> {
+    let digits = line.find(|ch: char| !ch.is_ascii_digit())?;
+    if digits == 0 || digits > MAX_LIST_DIGITS {
+        return None;
+    }
+    let rest = line[digits..].strip_prefix(['.', ')'])?.strip_prefix(' ')?;
+    Some((line[..digits].parse().ok()?, rest.trim_start()))
+}
+
+fn parse_task(content: &str) -> Option<(bool, &str)> {
+    let done = match content.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    let rest = &content[3..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some((done, rest.trim_start()))
+}
+
+fn is_slide_break(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.len() >= 3 && trimmed.chars().all(|ch| ch == '-')
+}
+
+fn classify_segment(line: &str, rules: &RuleSet) -> Segment {
+    let (align, text) = parse_align(line.trim());
+    classify_line(text, rules, indent_level(line)).with_align(align)
+}
+
+/// Poziom wcięcia linii: dwie spacje albo tabulator na poziom.
+fn indent_level(line: &str) -> usize {
+    let columns: usize = line
+        .chars()
+        .map_while(|ch| match ch {
+            ' ' => Some(1),
+            '\t' => Some(BULLET_INDENT.len()),
+            _ => None,
+        })
+        .sum();
+    (columns / BULLET_INDENT.len()).min(MAX_BULLET_INDENT)
+}
+
+/// Znacznik wyrównania z początku linii (`:^:` – do środka, `:>:` – do
+/// prawej, `:<:` – do lewej); bez znacznika segment jest wyrównany do lewej.
+fn parse_align(line: &str) -> (Align, &str) {
+    ALIGN_MARKERS
+        .iter()
+        .find_map(|(marker, align)| {
+            line.strip_prefix(marker)
+                .map(|rest| (*align, rest.trim_start()))
+        })
+        .unwrap_or((Align::Left, line))
+}
+
+fn classify_line(line: &str, rules: &RuleSet, indent: usize) -> Segment {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return Segment::new(SegmentKind::Plain(Vec::new()));
+    }
+
+    if let Some((style, text)) = rules.classify(trimmed) {
+        return Segment::new(SegmentKind::Custom {
+            style,
+            text: vec![Span::plain(text)],
+        });
+    }
+
+    if is_slide_break(trimmed) {
+        return Segment::new(SegmentKind::SlideBreak);
+    }
+
+    if trimmed.len() >= 3 && trimmed.chars().all(|ch| matches!(ch, '-' | '–' | '=')) {
+        return Segment::new(SegmentKind::Separator);
+    }
+
+    if trimmed.starts_with('#') {
+        let content = trimmed.trim_start_matches('#');
+        let level = trimmed.len() - content.len();
+        let content = content.trim();
+        if !content.is_empty() {
+            return Segment::new(SegmentKind::Heading(parse_inline(content)))
+                .with_heading_level(level);
+        }
+    }
+
+    if trimmed.starts_with("- ") || trimmed.starts_with("* ") {
+        let content = trimmed[2..].trim_start();
+        if let Some((done, text)) = parse_task(content) {
+            return Segment::new(SegmentKind::Task {
+                done,
+                text: parse_inline(text),
+            });
+        }
+        return Segment::new(SegmentKind::Bullet {
+            indent,
+            text: parse_inline(content),
+        });
+    }
+
+    if let Some((number, content)) = parse_numbered(trimmed) {
+        return Segment::new(SegmentKind::Numbered {
+            number,
+            text: parse_inline(content),
+        });
+    }
+
+    if let Some(cells) = table::parse_row(trimmed) {
+        return Segment::new(SegmentKind::Table {
+            headers: cells,
+            rows: Vec::new(),
+        });
+    }
+
+    if trimmed.starts_with('>') {
+        let content = trimmed.trim_start_matches('>').trim_start();
+        return Segment::new(SegmentKind::Callout(parse_inline(content)));
+    }
+
+    Segment::new(SegmentKind::Plain(parse_inline(trimmed)))
+}
+
+/// Cały program: argumenty z wiersza poleceń, wczytanie talii i prezentacja.
+pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+    let cli = Cli::parse();
+    if let Some(count) = cli.generate_demo {
+        let script = demo::generate(count, cli.seed);
+        match cli.demo_output.as_deref() {
+            Some(path) => std::fs::write(path, script)?,
+            None => print!("{}", script),
+        }
+        return Ok(());
+    }
+
+    let mut config = Config::from_sources(&cli)?;
+    let rules = match cli.rules.as_deref() {
+        Some(path) => rules::load_from_path(path)?,
+        None => RuleSet::default(),
+    };
+
+    let sources = sources::expand(&cli.scripts, cli.sort)?;
+    let ending = match &cli.end_slide {
+        Some(Some(path)) => Some(Slide::new(
+            load_segments(path, &rules, cli.reflow)?,
+            path,
+            None,
+        )),
+        Some(None) => Some(Slide::new(
+            vec![Segment::new(SegmentKind::Heading(vec![Span::plain("FIN")]))],
+            PathBuf::new(),
+            None,
+        )),
+        None => None,
+    };
+
+    // Katalogi są rozwijane przy każdym wczytaniu, więc `--watch` widzi
+    // dodane i usunięte pliki.
+    let load = || {
+        let sources = sources::expand(&cli.scripts, cli.sort)?;
+        let slides = load_deck(
+            &sources.scripts,
+            cli.join,
+            cli.reflow,
+            cli.split_on_heading,
+            &rules,
+        )?;
+        Ok(if cli.toc {
+            with_contents(slides)
+        } else {
+            slides
+        })
+    };
+
+    if cli.dry_run {
+        return dry_run(&config, &load()?);
+    }
+
+    if let Some(output) = cli.export_pdf.as_deref() {
+        let slides = load()?;
+        export::export_pdf(&config, &slides, output)?;
+        println!("PDF :: {}", output.display());
+        return Ok(());
+    }
+
+    if let Some(directory) = cli.export_svg.as_deref() {
+        let slides = load()?;
+        let written = export::export_svg(&config, &slides, directory)?;
+        println!("SVG :: {} ({} plików)", directory.display(), written);
+        return Ok(());
+    }
+
+    if let Some(banner_path) = config.banner_path()
+        && config.sticky_banner().is_none()
+    {
+        let banner = load_banner(banner_path)?;
+        display_banner(&config, &banner)?;
+        println!();
+        // Kolory zmienia dopiero pętla prezentacji, więc start nie czeka na klawisz.
+        if config.banner_color_cycle()
+            && let Ok((_, row)) = cursor::position()
+            && let Some(top) = row.checked_sub(banner.lines().count() as u16 + 1)
+        {
+            config.set_printed_banner(Some((top, banner)));
+        }
+    }
+
+    retro_separator(&config, config.presentation_title());
+    print_session_meta(&config, &sources.scripts);
+    for skipped in &sources.skipped {
+        println!(
+            "{}⚠ pominięto {}{}",
+            config.color_dim(),
+            skipped.display(),
+            config.style(RESET)
+        );
+    }
+
+    let slides = load()?;
+
+    if slides.is_empty() {
+        let mut stdout = io::stdout();
+        print_sticky_banner(&mut stdout, &config, ColorSlot::Glow)?;
+        print_frame_top(&mut stdout, &config, None, None)?;
+        print_empty_frame_message(&config)?;
+        print_frame_bottom(&mut stdout, &config, None)?;
+        println!(
+            "{}⚠ {}{}Brak treści do wyświetlenia{}",
+            config.color_dim(),
+            config.color_accent(),
+            config.style(ITALIC),
+            config.style(RESET)
+        );
+        println!();
+        return Ok(());
+    }
+
+    config.validate_pause_at(slides.len())?;
+    // Przewinięty terminal przesunąłby baner startowy, a na alternatywnym
+    // ekranie go nie widać – wtedy zostaje w kolorze `glow`.
+    let scrolled = || {
+        cursor::position()
+            .and_then(|(_, row)| terminal::size().map(|(_, rows)| row + 1 >= rows))
+            .unwrap_or(true)
+    };
+    if config.printed_banner().is_some() && (config.alt_screen() || scrolled()) {
+        config.set_printed_banner(None);
+    }
+    run_presentation(
+        &mut config,
+        slides,
+        ending.as_ref(),
+        cli.watch
+            .then(|| DeckReload {
+                load: &load,
+                directories: cli
+                    .scripts
+                    .iter()
+                    .filter(|path| path.is_dir())
+                    .cloned()
+                    .collect(),
+            })
+            .as_ref(),
+    )?;
+
+    println!();
+
+    Ok(())
+}
+
+fn load_segments(path: &Path, rules: &RuleSet, reflow: bool) -> io::Result<Vec<Segment>> {
+    let file = File::open(path)
+        .map_err(|error| io::Error::new(error.kind(), format!("{}: {}", path.display(), error)))?;
+    let segments = merge_tables(merge_quotes(parse_segments(BufReader::new(file), rules)?));
+    Ok(if reflow {
+        reflow_paragraphs(segments)
+    } else {
+        segments
+    })
+}
+
+fn load_deck(
+    paths: &[PathBuf],
+    join: JoinMode,
+    reflow: bool,
+    split_on_heading: bool,
+    rules: &RuleSet,
+) -> io::Result<Vec<Slide>> {
+    let mut slides = Vec::new();
+    for path in paths {
+        let segments = load_segments(path, rules, reflow)?;
+        if join == JoinMode::Separator && !slides.is_empty() && !segments.is_empty() {
+            slides.push(Slide::new(
+                vec![Segment::new(SegmentKind::Separator)],
+                path,
+                None,
+            ));
+        }
+        slides.extend(
+            group_segments(segments, split_on_heading)
+                .into_iter()
+                .enumerate()
+                .map(|(index, segments)| Slide::new(segments, path, Some(index + 1))),
+        );
+    }
+    Ok(slides)
+}
+
+/// Linie `---` dzielą plik na slajdy (puste slajdy są pomijane); bez nich
+/// każda linia jest osobną sekwencją, chyba że `--split-on-heading` grupuje
+/// je według nagłówków.
+fn group_segments(segments: Vec<Segment>, split_on_heading: bool) -> Vec<Vec<Segment>> {
+    let delimited = segments
+        .iter()
+        .any(|segment| matches!(segment.kind, SegmentKind::SlideBreak));
+    let is_notes = |segment: &Segment| matches!(segment.kind, SegmentKind::Notes(_));
+    if !delimited && !split_on_heading {
+        let mut groups: Vec<Vec<Segment>> = Vec::new();
+        for segment in segments {
+            match groups.last_mut() {
+                Some(group) if is_notes(&segment) => group.push(segment),
+                _ => groups.push(vec![segment]),
+            }
+        }
+        return groups;
+    }
+
+    let mut groups: Vec<Vec<Segment>> = vec![Vec::new()];
+    for segment in segments {
+        if let SegmentKind::SlideBreak = segment.kind {
+            groups.push(Vec::new());
+            continue;
+        }
+        match groups.last_mut() {
+            Some(group) if !split_on_heading || !segment.top_level_heading() => group.push(segment),
+            _ => groups.push(vec![segment]),
+        }
+    }
+    groups.retain(|group| {
+        group.iter().any(|segment| {
+            !is_notes(segment)
+                && !matches!(&segment.kind, SegmentKind::Plain(spans) if spans.is_empty())
+        })
+    });
+    groups
+}
+
+fn load_banner(path: &Path) -> io::Result<String> {
+    std::fs::read_to_string(path).map_err(|error| {
+        io::Error::new(
+            error.kind(),
+            format!("Baner ({}) nie został wczytany: {}", path.display(), error),
+        )
+    })
+}
+
+fn dry_run(config: &Config, slides: &[Slide]) -> Result<(), Box<dyn std::error::Error>> {
+    config.validate_pause_at(slides.len())?;
+    if config.frame_width() < MIN_FRAME_WIDTH {
+        return Err(format!(
+            "Szerokość ramki {} jest mniejsza niż minimalne {}",
+            config.frame_width(),
+            MIN_FRAME_WIDTH
+        )
+        .into());
+    }
+
+    if let Some(banner_path) = config.banner_path() {
+        load_banner(banner_path)?;
+    }
+    for (index, slide) in slides.iter().enumerate() {
+        if let Some(Err(error)) = slide.theme() {
+            eprintln!(
+                "⚠ sekwencja {}: {} – użyto motywu prezentacji",
+                index + 1,
+                error
+            );
+        }
+    }
+
+    println!(
+        "OK: {} sekwencji, motyw {}, ramka {}",
+        slides.len(),
+        config.upcase(config.theme_label()),
+        config.frame_width()
+    );
+    Ok(())
+}
+
+/// Ponowne wyświetlenie banera po zmianie jego pliku w trybie `--watch`:
+/// ekran jest czyszczony, a baner rysowany od nowa razem z rozgrzewką CRT.
+/// Zwraca wiersz, od którego zaczyna się ramka, albo `None`, gdy pliku
+/// chwilowo brak lub jest pusty (zapis w toku) – zostaje wtedy poprzedni obraz.
+pub(crate) fn redisplay_banner(config: &Config) -> io::Result<Option<u16>> {
+    let Some(banner) = config
+        .banner_path()
+        .and_then(|path| load_banner(path).ok())
+        .filter(|banner| !banner.trim().is_empty())
+    else {
+        return Ok(None);
+    };
+    let mut stdout = io::stdout();
+    stdout
+        .queue(cursor::MoveTo(0, 0))?
+        .queue(terminal::Clear(ClearType::All))?;
+    stdout.flush()?;
+    terminal::disable_raw_mode()?;
+    let shown = display_banner(config, &banner).map(|()| println!());
+    terminal::enable_raw_mode()?;
+    shown?;
+    let rows = banner.lines().count() as u16 + 1;
+    Ok(Some(cursor::position().map_or(rows, |(_, row)| row)))
+}
+
+fn display_banner(config: &Config, banner: &str) -> io::Result<()> {
+    crt_warmup(config)?;
+    let mut stdout = io::stdout();
+    let banner_width = config.banner_width();
+    let lines: Vec<Cow<'_, str>> = banner
+        .lines()
+        .map(|line| clip_line(line, banner_width))
+        .collect();
+    let sweep_step = match lines.iter().map(|line| line.chars().count()).sum::<usize>() {
+        0 => BANNER_SWEEP_STEP,
+        glyphs => BANNER_SWEEP_STEP.min(BANNER_SWEEP_BUDGET / glyphs as u32),
+    };
+
+    for line in &lines {
+        if config.animations_enabled() && config.banner_gradient() {
+            sweep_banner_line(config, line, sweep_step)?;
+        } else if config.animations_enabled() {
+            println!("{}{}{}", config.color_dim(), line, config.style(RESET));
+            stdout.flush()?;
+            config.pause(Duration::from_millis(60));
+            print!(
+                "\x1b[1A\r{}{}{}{}\x1b[0K",
+                config.color_glow(),
+                config.style(BOLD),
+                line,
+                config.style(RESET)
+            );
+            stdout.flush()?;
+            println!();
+            config.pause(Duration::from_millis(110));
+        } else {
+            println!(
+                "{}{}{}{}",
+                config.color_glow(),
+                config.style(BOLD),
+                line,
+                config.style(RESET)
+            );
+        }
+    }
+
+    config.pause(Duration::from_millis(240));
+    Ok(())
+}
+
+pub(crate) fn banner_cycle_slot(phase: usize) -> ColorSlot {
+    BANNER_CYCLE_SLOTS[phase % BANNER_CYCLE_SLOTS.len()]
+}
+
+fn repaint_banner<L: AsRef<str>>(
+    out: &mut dyn Write,
+    config: &Config,
+    lines: &[L],
+    top: impl Command,
+    slot: ColorSlot,
+) -> io::Result<()> {
+    out.queue(cursor::SavePosition)?;
+    out.queue(top)?;
+    for line in lines {
+        write!(
+            out,
+            "{}{}{}{}",
+            config.color(slot),
+            config.style(BOLD),
+            line.as_ref(),
+            config.style(RESET)
+        )?;
+        out.queue(cursor::MoveToNextLine(1))?;
+    }
+    out.queue(cursor::RestorePosition)?;
+    out.flush()
+}
+
+fn sweep_banner_line(config: &Config, line: &str, step: Duration) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let glyphs: Vec<char> = line.chars().collect();
+    for beam in 0..glyphs.len() {
+        let lit: String = glyphs[..beam].iter().collect();
+        let unlit: String = glyphs[beam + 1..].iter().collect();
+        print!(
+            "\r{}{}{}{}{}{}{}{}{}\x1b[0K",
+            config.color_glow(),
+            config.style(BOLD),
+            lit,
+            config.style(SCAN_BEAM),
+            glyphs[beam],
+            config.style(RESET),
+            config.color_dim(),
+            unlit,
+            config.style(RESET)
+        );
+        stdout.flush()?;
+        config.pause(step);
+    }
+    println!(
+        "\r{}{}{}{}\x1b[0K",
+        config.color_glow(),
+        config.style(BOLD),
+        line,
+        config.style(RESET)
+    );
+    stdout.flush()
+}
+
+fn clip_line(line: &str, width: usize) -> Cow<'_, str> {
+    let fitted = fit_spans(&[Span::plain(line)], width);
+    if !fitted.truncated {
+        return Cow::Borrowed(line);
+    }
+
+    let mut clipped = plain_text(&fitted.spans);
+    clipped.push('…');
+    Cow::Owned(clipped)
+}
+
+pub(crate) fn print_sticky_banner(
+    out: &mut dyn Write,
+    config: &Config,
+    slot: ColorSlot,
+) -> io::Result<()> {
+    let Some(banner) = config.sticky_banner() else {
+        return Ok(());
+    };
+
+    let banner_width = config.banner_width();
+    for line in banner.lines() {
+        writeln!(
+            out,
+            "{}{}{}{}",
+            config.color(slot),
+            config.style(BOLD),
+            clip_line(line, banner_width),
+            config.style(RESET)
+        )?;
+    }
+    writeln!(out)
+}
+
+/// Przemalowuje baner przypięty nad ramką (od wiersza `origin_row`) albo
+/// wypisany przed prezentacją baner startowy.
+pub(crate) fn repaint_cycled_banner(
+    out: &mut dyn Write,
+    config: &Config,
+    origin_row: u16,
+    slot: ColorSlot,
+) -> io::Result<()> {
+    let (top, banner) = match (config.sticky_banner(), config.printed_banner()) {
+        (Some(banner), _) => (origin_row, banner),
+        (None, Some((top, banner))) => (top, banner),
+        (None, None) => return Ok(()),
+    };
+
+    let banner_width = config.banner_width();
+    let lines: Vec<Cow<'_, str>> = banner
+        .lines()
+        .map(|line| clip_line(line, banner_width))
+        .collect();
+    repaint_banner(out, config, &lines, cursor::MoveTo(0, top), slot)
+}
+
+pub(crate) fn transition_animation(out: &mut dyn Write, config: &Config) -> io::Result<()> {
+    if !config.animations_enabled() {
+        return Ok(());
+    }
+
+    let frames = [
+        "[⠁] synchronizacja torów",
+        "[⠃] kalibracja światła",
+        "[⠇] ładowanie wektorów",
+        "[⠇] montaż kadrów",
+        "[⠧] strojenie luminancji",
+        "[⠷] finalizacja",
+    ];
+    for frame in frames.iter().cycle().take(10) {
+        write!(
+            out,
+            "\r{}{}{}  ",
+            config.color_dim(),
+            frame,
+            config.style(RESET)
+        )?;
+        out.flush()?;
+        config.pause(Duration::from_millis(70));
+    }
+
+    write!(
+        out,
+        "\r{}{}[GOTOWE]{}",
+        config.color_glow(),
+        config.style(BOLD),
+        config.style(RESET)
+    )?;
+    out.flush()?;
+    config.pause(Duration::from_millis(210));
+    write!(out, "\r\x1b[0K")?;
+    out.flush()
+}
+
+/// Znacznik punktu wcięty o dwie kolumny na poziom; kolejne poziomy
+/// przechodzą cyklicznie przez znacznik motywu, `◦` i `▪`.
+pub(crate) fn bullet_glyph(glyphs: &ThemeGlyphs, indent: usize) -> String {
+    let glyph = match indent % (NESTED_BULLETS.len() + 1) {
+        0 => glyphs.bullet(),
+        level => NESTED_BULLETS[level - 1],
+    };
+    format!("{}{}", BULLET_INDENT.repeat(indent), glyph)
+}
+
+fn glyph_span(glyph: &str, leading: bool) -> Option<Span> {
+    if glyph.is_empty() {
+        None
+    } else if leading {
+        Some(Span::plain(format!("{} ", glyph)))
+    } else {
+        Some(Span::plain(format!(" {}", glyph)))
+    }
+}
+
+pub(crate) fn animate_line(
+    stdout: &mut dyn Write,
+    config: &Config,
+    index: usize,
+    segment: &Segment,
+    animate: bool,
+) -> io::Result<()> {
+    if let SegmentKind::Spacer(rows) = segment.kind() {
+        return print_spacer_rows(stdout, config, *rows);
+    }
+    if let Some(heading) = segment.heading()
+        && (config.heading_banner() || segment.banner_heading())
+    {
+        return print_heading_banner(stdout, config, &heading);
+    }
+
+    let framed = !config.compact_mode();
+    let sequence = index + 1;
+    let prefix_len = visible_width("│ ") + gutter_number_width(sequence) + " :: ".len();
+    let available = if framed {
+        config.frame_width().saturating_sub(prefix_len + 1)
+    } else {
+        config.frame_width()
+    };
+
+    if let SegmentKind::Quote(rows) = segment.kind() {
+        let inner = available.saturating_sub(visible_width(QUOTE_PREFIX));
+        let wrapped = rows
+            .iter()
+            .flat_map(|spans| wrap_spans(&expand_span_tokens(spans, config), inner, 0));
+        for (row, fitted) in wrapped.enumerate() {
+            if framed && row > 0 {
+                print_continuation_gutter(stdout, config, sequence)?;
+            } else if framed {
+                print_gutter(stdout, config, sequence)?;
+            }
+            write!(
+                stdout,
+                "{}{}{}{}",
+                config.color_glow(),
+                QUOTE_PREFIX,
+                config.style(RESET),
+                config.style(ITALIC)
+            )?;
+            for span in &fitted.spans {
+                let styled = write_span_escape(stdout, config, span.style())?;
+                write!(stdout, "{}", span.text())?;
+                if styled {
+                    write!(stdout, "{}{}", config.style(RESET), config.style(ITALIC))?;
+                }
+            }
+            write!(stdout, "{}", config.style(RESET))?;
+            if framed {
+                write!(
+                    stdout,
+                    "{:padding$}",
+                    "",
+                    padding = inner.saturating_sub(fitted.width)
+                )?;
+            }
+            close_frame_row(stdout, config)?;
+        }
+        return Ok(());
+    }
+
+    if let SegmentKind::Contents { title, slide } = segment.kind() {
+        let number = format!("{:02}", slide);
+        // Spacja, co najmniej dwa znaki wypełnienia i spacja przed numerem.
+        let room = available.saturating_sub(number.len() + 4);
+        let fitted = fit_spans(title, room);
+        if framed {
+            print_gutter(stdout, config, sequence)?;
+        }
+        write_spans(stdout, config, &fitted.spans, config.color_accent())?;
+        if fitted.truncated {
+            write!(stdout, "{}›{}", config.color_dim(), config.style(RESET))?;
+        }
+        let leaders = available.saturating_sub(fitted.width + number.len() + 2);
+        write!(
+            stdout,
+            "{} {} {}{}{}",
+            config.color_dim(),
+            CONTENTS_LEADER.repeat(leaders.max(1)),
+            config.color_glow(),
+            number,
+            config.style(RESET)
+        )?;
+        close_frame_row(stdout, config)?;
+        return Ok(());
+    }
+
+    if let SegmentKind::Table { headers, rows } = segment.kind() {
+        for (row, line) in table::table_lines(headers, rows, available)
+            .iter()
+            .enumerate()
+        {
+            if framed && row > 0 {
+                print_continuation_gutter(stdout, config, sequence)?;
+            } else if framed {
+                print_gutter(stdout, config, sequence)?;
+            }
+            let fitted = fit_spans(line, available);
+            write_spans(stdout, config, &fitted.spans, config.color_accent())?;
+            if fitted.truncated {
+                write!(stdout, "{}›{}", config.color_dim(), config.style(RESET))?;
+            }
+            if framed {
+                write!(
+                    stdout,
+                    "{:padding$}",
+                    "",
+                    padding = available.saturating_sub(fitted.width)
+                )?;
+            }
+            close_frame_row(stdout, config)?;
+        }
+        return Ok(());
+    }
+
+    if let SegmentKind::Code { language, lines } = segment.kind() {
+        let highlighted = language
+            .as_deref()
+            .filter(|_| config.syntax_highlight())
+            .and_then(|language| highlight_code(config, language, lines, available));
+        for (row, line) in lines.iter().enumerate() {
+            if framed {
+                print_gutter(stdout, config, sequence)?;
+            }
+            let text = match &highlighted {
+                Some(rows) => rows[row].clone(),
+                None => format!(
+                    "{}{}{}",
+                    config.color_dim(),
+                    plain_text(&code_row(line, available).spans),
+                    config.style(RESET)
+                ),
+            };
+            write!(stdout, "{}", text)?;
+            if framed {
+                write!(
+                    stdout,
+                    "{:padding$}",
+                    "",
+                    padding = available.saturating_sub(visible_width(&text))
+                )?;
+            }
+            close_frame_row(stdout, config)?;
+        }
+        return Ok(());
+    }
+
+    if let SegmentKind::Separator | SegmentKind::SlideBreak = segment.kind() {
+        if framed {
+            print_gutter(stdout, config, sequence)?;
+        }
+        write!(stdout, "{}", config.color_dim())?;
+        for _ in 0..available {
+            write!(stdout, "─")?;
+        }
+        write!(stdout, "{}", config.style(RESET))?;
+        close_frame_row(stdout, config)?;
+    } else {
+        let spans = expand_span_tokens(segment.spans().unwrap_or_default(), config);
+        let blank = spans_width(&spans) == 0;
+        let (lead, body, color, style_prefix, delay): (_, Cow<[Span]>, _, _, _) =
+            match segment.kind() {
+                SegmentKind::Heading(_) => (
+                    glyph_span(config.glyphs().heading_prefix(), true),
+                    spans
+                        .iter()
+                        .map(|span| span.map_text(|text| config.upcase(text)))
+                        .collect(),
+                    config.color_glow(),
+                    Some(Cow::Owned(format!(
+                        "{}{}",
+                        config.style(BOLD),
+                        config.style(UNDERLINE)
+                    ))),
+                    Duration::from_millis(35),
+                ),
+                SegmentKind::Bullet { indent, .. } => (
+                    glyph_span(&bullet_glyph(config.glyphs(), *indent), true),
+                    spans,
+                    config.color_accent(),
+                    None,
+                    Duration::from_millis(45),
+                ),
+                SegmentKind::Numbered { number, .. } => (
+                    Some(Span::plain(format!("{}. ", number))),
+                    spans,
+                    config.color_accent(),
+                    None,
+                    Duration::from_millis(45),
+                ),
+                SegmentKind::Callout(_) => (
+                    glyph_span(config.glyphs().callout_open(), true),
+                    spans
+                        .iter()
+                        .cloned()
+                        .chain(glyph_span(config.glyphs().callout_close(), false))
+                        .collect(),
+                    config.color_glow(),
+                    Some(Cow::Borrowed(config.style(ITALIC))),
+                    Duration::from_millis(38),
+                ),
+                SegmentKind::Plain(_) => (
+                    None,
+                    spans,
+                    if blank {
+                        config.color_dim()
+                    } else {
+                        config.color_accent()
+                    },
+                    None,
+                    Duration::from_millis(55),
+                ),
+                SegmentKind::Custom { style, .. } => (
+                    style
+                        .marker()
+                        .map(|marker| Span::plain(format!("{} ", marker))),
+                    spans,
+                    config.color(style.color()),
+                    Some(format!(
+                        "{}{}{}",
+                        if style.bold() { config.style(BOLD) } else { "" },
+                        if style.italic() {
+                            config.style(ITALIC)
+                        } else {
+                            ""
+                        },
+                        if style.underline() {
+                            config.style(UNDERLINE)
+                        } else {
+                            ""
+                        }
+                    ))
+                    .filter(|prefix| !prefix.is_empty())
+                    .map(Cow::Owned),
+                    Duration::from_millis(45),
+                ),
+                SegmentKind::Task { done, .. } => (
+                    glyph_span(
+                        if *done {
+                            config.glyphs().task_done()
+                        } else {
+                            config.glyphs().task_open()
+                        },
+                        true,
+                    ),
+                    spans,
+                    if *done {
+                        config.color_glow()
+                    } else {
+                        config.color_dim()
+                    },
+                    None,
+                    Duration::from_millis(45),
+                ),
+                SegmentKind::Separator
+                | SegmentKind::SlideBreak
+                | SegmentKind::Spacer(_)
+                | SegmentKind::Code { .. }
+                | SegmentKind::Quote(_)
+                | SegmentKind::Table { .. }
+                | SegmentKind::Contents { .. }
+                | SegmentKind::Notes(_) => unreachable!(),
+            };
+
+        let hang = lead.as_ref().map_or(0, Span::width);
+        let display_spans = match lead {
+            Some(lead) => {
+                let mut display = Vec::with_capacity(body.len() + 1);
+                display.push(lead);
+                match body {
+                    Cow::Borrowed(body) => display.extend_from_slice(body),
+                    Cow::Owned(body) => display.extend(body),
+                }
+                Cow::Owned(display)
+            }
+            None => body,
+        };
+        let display_spans = match config.highlight() {
+            Some(pattern) => Cow::Owned(highlight_spans(&display_spans, pattern)),
+            None => display_spans,
+        };
+        let style_prefix_ref = style_prefix.as_deref().unwrap_or("");
+        let wrapped;
+        let fitted;
+        let rows = if config.wrap() {
+            wrapped = wrap_spans(&display_spans, available, hang);
+            wrapped.as_slice()
+        } else {
+            fitted = fit_spans(&display_spans, available);
+            std::slice::from_ref(&fitted)
+        };
+
+        let typewriter = animate && config.animations_enabled() && !segment.instant();
+        let total_glyphs: usize = rows
+            .iter()
+            .flat_map(|row| &row.spans)
+            .map(|span| span.text().chars().count())
+            .sum();
+        let mut revealed = 0;
+        for (row, fitted) in rows.iter().enumerate() {
+            let offset = if row > 0 { hang } else { 0 };
+            let free = available.saturating_sub(offset + fitted.width);
+            let (indent, padding) = match segment.align() {
+                Align::Left => (offset, free),
+                Align::Center => (offset + free / 2, free - free / 2),
+                Align::Right => (offset + free, 0),
+            };
+            if framed {
+                if row > 0 {
+                    print_continuation_gutter(stdout, config, sequence)?;
+                } else {
+                    print_gutter(stdout, config, sequence)?;
+                }
+            }
+            write!(stdout, "{:indent$}", "")?;
+
+            if available > 0 && (!fitted.spans.is_empty() || !style_prefix_ref.is_empty()) {
+                write!(stdout, "{}{}", style_prefix_ref, color)?;
+                stdout.flush()?;
+
+                for span in &fitted.spans {
+                    let link = span.link().filter(|_| config.hyperlinks());
+                    if let Some(url) = link {
+                        let url: String = url.chars().filter(|ch| !ch.is_control()).collect();
+                        write!(stdout, "\x1b]8;;{}\x07", url)?;
+                    }
+                    let styled = write_span_escape(stdout, config, span.style())?;
+                    if typewriter {
+                        for ch in span.text().chars() {
+                            write!(stdout, "{}", ch)?;
+                            if let Some(cursor) = config.typing_cursor() {
+                                write!(stdout, "{}\x08", cursor.glyph())?;
+                            }
+                            stdout.flush()?;
+                            config.pause(config.typing_delay(delay, revealed, total_glyphs));
+                            revealed += 1;
+                        }
+                    } else {
+                        write!(stdout, "{}", span.text())?;
+                    }
+                    if styled {
+                        write!(
+                            stdout,
+                            "{}{}{}",
+                            config.style(RESET),
+                            style_prefix_ref,
+                            color
+                        )?;
+                    }
+                    if link.is_some() {
+                        write!(stdout, "\x1b]8;;\x07")?;
+                    }
+                }
+
+                if typewriter && config.typing_cursor().is_some() {
+                    write!(stdout, " \x08")?;
+                }
+
+                if fitted.truncated {
+                    write!(stdout, "›")?;
+                    stdout.flush()?;
+                }
+
+                write!(stdout, "{}", config.style(RESET))?;
+            }
+
+            if framed && padding > 0 {
+                write!(
+                    stdout,
+                    "{}{:padding$}{}",
+                    config.color_dim(),
+                    "",
+                    config.style(RESET),
+                    padding = padding
+                )?;
+            }
+            close_frame_row(stdout, config)?;
+        }
+    }
+    stdout.flush()?;
+
+    Ok(())
+}
+
+/// Fragmenty z rozwiniętymi znacznikami zegara; bez znacznika `@` nic nie
+/// jest kopiowane.
+fn expand_span_tokens<'a>(spans: &'a [Span], config: &Config) -> Cow<'a, [Span]> {
+    if !spans.iter().any(|span| span.text().contains('@')) {
+        return Cow::Borrowed(spans);
+    }
+    spans
+        .iter()
+        .map(|span| {
+            span.map_text(|text| clock::expand_tokens(text, config.time_format()).into_owned())
+        })
+        .collect()
+}
+
+/// Szerokość numeru sekwencji w marginesie – co najmniej trzy cyfry.
+fn gutter_number_width(number: usize) -> usize {
+    number
+        .checked_ilog10()
+        .map_or(1, |digits| digits as usize + 1)
+        .max(3)
+}
+
+fn print_gutter(out: &mut dyn Write, config: &Config, number: usize) -> io::Result<()> {
+    write!(
+        out,
+        "{}{}│ {}{:03}{}{} :: {}",
+        config.color_background(),
+        config.color_dim(),
+        config.gutter_number_color(),
+        number,
+        config.style(RESET),
+        config.gutter_separator_color(),
+        config.style(RESET)
+    )?;
+    out.flush()
+}
+
+/// Wiersz kontynuacji przy `--wrap`: sama krawędź ramki, a tekst zaczyna
+/// się pod tekstem pierwszego wiersza, za numerem sekwencji.
+fn print_continuation_gutter(
+    out: &mut dyn Write,
+    config: &Config,
+    number: usize,
+) -> io::Result<()> {
+    write!(
+        out,
+        "{}{}│{}{:width$}",
+        config.color_background(),
+        config.color_dim(),
+        config.style(RESET),
+        "",
+        width = " ".len() + gutter_number_width(number) + " :: ".len()
+    )
+}
+
+/// Wiersz bloku kodu nie jest zawijany ani zakończony `›` – gdy nie mieści
+/// się w ramce, ostatnią kolumnę zajmuje wskaźnik przewijania `»`.
+pub(crate) fn code_row(line: &str, available: usize) -> FittedLine {
+    let mut fitted = fit_spans(&[Span::plain(line)], available);
+    if fitted.truncated {
+        fitted.spans.push(Span::plain(SCROLL_MARKER));
+        fitted.truncated = false;
+    }
+    fitted
+}
+
+fn close_frame_row(out: &mut dyn Write, config: &Config) -> io::Result<()> {
+    if config.compact_mode() {
+        return writeln!(out);
+    }
+    writeln!(out, "{}│{}", config.color_dim(), config.row_end())
+}
+
+/// Fragmenty bez animacji w kolorze `color`, z własnym stylem i odnośnikami.
+fn write_spans(
+    out: &mut dyn Write,
+    config: &Config,
+    spans: &[Span],
+    color: &str,
+) -> io::Result<()> {
+    for span in spans {
+        let link = span.link().filter(|_| config.hyperlinks());
+        if let Some(url) = link {
+            let url: String = url.chars().filter(|ch| !ch.is_control()).collect();
+            write!(out, "\x1b]8;;{}\x07", url)?;
+        }
+        write!(out, "{}", color)?;
+        write_span_escape(out, config, span.style())?;
+        write!(out, "{}{}", span.text(), config.style(RESET))?;
+        if link.is_some() {
+            write!(out, "\x1b]8;;\x07")?;
+        }
+    }
+    Ok(())
+}
+
+fn write_span_escape(out: &mut dyn Write, config: &Config, style: SpanStyle) -> io::Result<bool> {
+    if style.bold {
+        write!(out, "{}", config.style(BOLD))?;
+    }
+    if style.italic {
+        write!(out, "{}", config.style(ITALIC))?;
+    }
+    if style.underline {
+        write!(out, "{}", config.style(UNDERLINE))?;
+    }
+    if let Some(slot) = style.color {
+        write!(out, "{}", config.color(slot))?;
+    }
+    Ok(!style.is_plain())
+}
+
+pub(crate) fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+    let mut in_osc = false;
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if in_osc {
+            in_osc = ch != '\x07';
+        } else if in_escape {
+            if ch.is_ascii_alphabetic() {
+                in_escape = false;
+            }
+        } else if ch == '\x1b' && chars.next_if_eq(&']').is_some() {
+            in_osc = true;
+        } else if ch == '\x1b' {
+            in_escape = true;
+        } else {
+            width += span::char_width(ch);
+        }
+    }
+    width
+}
+
+fn print_session_meta(config: &Config, scripts: &[PathBuf]) {
+    let sources = scripts
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    println!(
+        "{}SOURCE :: {}{}{}{}",
+        config.color_dim(),
+        config.style(BOLD),
+        config.color_accent(),
+        sources,
+        config.style(RESET)
+    );
+    println!(
+        "{}THEME  :: {}{}{}{}  {}FRAME :: {}{}{}{}  {}MODE :: {}{}{}{}  {}BG :: {}{}{}{}",
+        config.color_dim(),
+        config.style(BOLD),
+        config.color_glow(),
+        config.upcase(config.theme_label()),
+        config.style(RESET),
+        config.color_dim(),
+        config.style(BOLD),
+        config.color_accent(),
+        config.frame_width(),
+        config.style(RESET),
+        config.color_dim(),
+        config.style(BOLD),
+        config.color_accent(),
+        if config.animations_enabled() {
+            "CINEMATIC"
+        } else {
+            "INSTANT"
+        },
+        config.style(RESET),
+        config.color_dim(),
+        config.style(BOLD),
+        config.color_accent(),
+        config.background(),
+        config.style(RESET)
+    );
+    if config.deck_info() {
+        let info = deck_info::collect(scripts);
+        let field = |label: &str, value: &str| {
+            format!(
+                "{}{} :: {}{}{}{}",
+                config.color_dim(),
+                label,
+                config.style(BOLD),
+                config.color_accent(),
+                value,
+                config.style(RESET)
+            )
+        };
+        let mut fields = vec![
+            field("DECK  ", info.modified.as_deref().unwrap_or("?")),
+            field("VER", info.version),
+        ];
+        if let Some(commit) = &info.commit {
+            fields.push(field("GIT", commit));
+        }
+        println!("{}", fields.join("  "));
+    }
+    println!();
+}
+
+fn retro_separator(config: &Config, label: &str) {
+    let label = format!("╢ {} ╟", config.upcase(label));
+    let fill = config.frame_width().saturating_sub(visible_width(&label));
+    let left = fill / 2;
+    let right = fill - left;
+
+    println!(
+        "{}{}{}{}{}{}{}",
+        config.color_dim(),
+        "═".repeat(left),
+        config.color_glow(),
+        label,
+        config.color_dim(),
+        "═".repeat(right),
+        config.style(RESET)
+    );
+}
+
+pub(crate) fn print_frame_top(
+    out: &mut dyn Write,
+    config: &Config,
+    title: Option<&str>,
+    corner: Option<&str>,
+) -> io::Result<()> {
+    if config.compact_mode() {
+        return Ok(());
+    }
+
+    let inner = config.frame_width().saturating_sub(2);
+    let corner = corner.filter(|corner| visible_width(corner) + 5 <= inner);
+    let corner_width = corner.map_or(0, |corner| visible_width(corner) + 5);
+    let available = inner.saturating_sub(corner_width + 5);
+    let title = title
+        .filter(|_| available > 0)
+        .map(|title| clip_line(title, available));
+    let title_width = title.as_ref().map_or(0, |title| visible_width(title) + 5);
+
+    let mut border = format!("{}{}╭", config.color_background(), config.color_dim());
+    if let Some(title) = &title {
+        border.push_str(&format!(
+            "─┤ {}{}{}{} ├",
+            config.style(RESET),
+            config.color_glow(),
+            title,
+            config.color_dim()
+        ));
+    }
+    border.push_str(&"─".repeat(inner.saturating_sub(title_width + corner_width)));
+    if let Some(corner) = corner {
+        border.push_str(&format!(
+            "┤ {}{}{}{} ├─",
+            config.style(RESET),
+            config.color_accent(),
+            corner,
+            config.color_dim()
+        ));
+    }
+    writeln!(out, "{}╮{}", border, config.row_end())
+}
+
+/// Dolna krawędź ramki; `note` trafia do jej prawego rogu (w trybie
+/// kompaktowym – do osobnego wiersza), jak numer w górnej krawędzi.
+pub(crate) fn print_frame_bottom(
+    out: &mut dyn Write,
+    config: &Config,
+    note: Option<&str>,
+) -> io::Result<()> {
+    if config.compact_mode() {
+        if let Some(note) = note {
+            writeln!(out, "{}{}{}", config.color_dim(), note, config.style(RESET))?;
+        }
+        return Ok(());
+    }
+
+    let inner = config.frame_width().saturating_sub(2);
+    let note = note.filter(|note| visible_width(note) + 5 <= inner);
+    let note_width = note.map_or(0, |note| visible_width(note) + 5);
+    let mut border = format!(
+        "{}{}╰{}",
+        config.color_background(),
+        config.color_dim(),
+        "─".repeat(inner - note_width)
+    );
+    if let Some(note) = note {
+        border.push_str(&format!(
+            "┤ {}{}{}{} ├─",
+            config.style(RESET),
+            config.color_accent(),
+            note,
+            config.color_dim()
+        ));
+    }
+    writeln!(out, "{}╯{}", border, config.row_end())
+}
+
+fn print_heading_banner(out: &mut dyn Write, config: &Config, heading: &str) -> io::Result<()> {
+    let (background, border, inner) = if config.compact_mode() {
+        ("", "", config.frame_width())
+    } else {
+        (
+            config.color_background(),
+            "│",
+            config.frame_width().saturating_sub(2),
+        )
+    };
+    let text = config.upcase(heading);
+    // Bez kolorów odwrócone tło znika, więc pasek zastępują nawiasy.
+    let text = if config.no_color() {
+        Cow::Owned(format!("[ {} ]", clip_line(&text, inner.saturating_sub(6))))
+    } else {
+        clip_line(&text, inner.saturating_sub(2))
+    };
+    let fill = inner.saturating_sub(visible_width(&text));
+    let left = fill / 2;
+    writeln!(
+        out,
+        "{}{}{}{}{}{}{}{}{}{}{}{}{}{}",
+        background,
+        config.color_dim(),
+        border,
+        config.style(RESET),
+        config.color_glow(),
+        config.style(BOLD),
+        config.style(REVERSE),
+        " ".repeat(left),
+        text,
+        " ".repeat(fill - left),
+        config.style(RESET),
+        config.color_dim(),
+        border,
+        config.row_end()
+    )
+}
+
+pub(crate) fn print_spacer_rows(
+    out: &mut dyn Write,
+    config: &Config,
+    count: usize,
+) -> io::Result<()> {
+    for _ in 0..count {
+        if config.compact_mode() {
+            writeln!(out)?;
+            continue;
+        }
+        writeln!(
+            out,
+            "{}{}│{}│{}",
+            config.color_background(),
+            config.color_dim(),
+            " ".repeat(config.frame_width().saturating_sub(2)),
+            config.row_end()
+        )?;
+    }
+    Ok(())
+}
+
+fn print_empty_frame_message(config: &Config) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let prefix = if config.compact_mode() {
+        "SYS :: "
+    } else {
+        "│ SYS :: "
+    };
+    let available = config
+        .frame_width()
+        .saturating_sub(visible_width(prefix) + 1);
+    let fitted = fit_spans(&[Span::plain("(brak treści w pliku)")], available);
+
+    if !config.compact_mode() {
+        print!("{}", config.color_background());
+    }
+    print!("{}{}{}", config.color_dim(), prefix, config.style(RESET));
+    stdout.flush()?;
+
+    for ch in plain_text(&fitted.spans).chars() {
+        print!("{}{}{}", config.style(ITALIC), config.color_dim(), ch);
+        stdout.flush()?;
+    }
+    if fitted.truncated {
+        print!("›");
+    }
+    print!("{}", config.style(RESET));
+
+    let padding = available.saturating_sub(fitted.width);
+    if padding > 0 && !config.compact_mode() {
+        print!(
+            "{}{}{}",
+            config.color_dim(),
+            " ".repeat(padding),
+            config.style(RESET)
+        );
+    }
+    stdout.flush()?;
+    close_frame_row(&mut stdout, config)
+}
+
+fn crt_warmup(config: &Config) -> io::Result<()> {
+    if !config.animations_enabled() {
+        return Ok(());
+    }
+
+    let mut stdout = io::stdout();
+    let phases = [
+        "[.. ] spinning up retro tube",
+        "[<. ] calibrating scanline",
+        "[<<.] loading rust pigment",
+        "[<<<] ready to beam",
+    ];
+
+    for phase in &phases {
+        print!("\r{}{}{}", config.color_dim(), phase, config.style(RESET));
+        stdout.flush()?;
+        config.pause(Duration::from_millis(220));
+    }
+
+    print!("\r\x1b[0K");
+    stdout.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_parser_keeps_directive_state_between_calls() {
+        let rules = RuleSet::default();
+        let input = "@frame-title Agenda\n@instant\n\n# Start\n- punkt\n";
+        let mut parser = SegmentParser::new(input.as_bytes(), &rules);
+
+        let blank = parser.next().unwrap().unwrap();
+        assert_eq!(blank.frame_title(), Some("Agenda"));
+        assert!(blank.instant());
+
+        let heading = parser.next().unwrap().unwrap();
+        assert_eq!(heading.heading().as_deref(), Some("Start"));
+        assert_eq!(heading.frame_title(), None);
+        assert!(!heading.instant());
+
+        assert!(matches!(
+            parser.next().unwrap().unwrap().kind(),
+            SegmentKind::Bullet { .. }
+        ));
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn segment_parser_joins_continued_lines() {
+        let rules = RuleSet::default();
+        let input = "- długi \\\n  punkt\n- jeden\\\ndwa \\\n   trzy\n";
+        let texts: Vec<String> = SegmentParser::new(input.as_bytes(), &rules)
+            .map(|segment| plain_text(segment.unwrap().spans().unwrap()))
+            .collect();
+
+        assert_eq!(texts, ["długi punkt", "jeden dwa trzy"]);
+    }
+
+    #[test]
+    fn segment_parser_keeps_literal_backslashes() {
+        let rules = RuleSet::default();
+        let input = "C:\\\\\nkoniec \\";
+        let texts: Vec<String> = SegmentParser::new(input.as_bytes(), &rules)
+            .map(|segment| plain_text(segment.unwrap().spans().unwrap()))
+            .collect();
+
+        assert_eq!(texts, ["C:\\", "koniec \\"]);
+    }
+
+    #[test]
+    fn segment_parser_collects_fenced_code() {
+        let rules = RuleSet::default();
+        let input = "```rust ignore\nfn main() {\n\tprintln!(\"\\\\\");\n}\n```\n# Dalej\n```\n- surowa linia \\\n";
+        let segments = parse_segments(input.as_bytes(), &rules).unwrap();
+
+        assert_eq!(segments.len(), 3);
+        match segments[0].kind() {
+            SegmentKind::Code { language, lines } => {
+                assert_eq!(language.as_deref(), Some("rust"));
+                assert_eq!(lines, &["fn main() {", "    println!(\"\\\\\");", "}"]);
+            }
+            kind => panic!("oczekiwano bloku kodu, jest {:?}", kind),
+        }
+        assert_eq!(segments[1].heading().as_deref(), Some("Dalej"));
+        match segments[2].kind() {
+            SegmentKind::Code { language, lines } => {
+                assert_eq!(language, &None);
+                assert_eq!(lines, &["- surowa linia \\"]);
+            }
+            kind => panic!("oczekiwano bloku kodu, jest {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn classifies_task_list_items() {
+        let rules = RuleSet::default();
+        let task = |line: &str| match classify_segment(line, &rules).kind {
+            SegmentKind::Task { done, text } => Some((done, plain_text(&text))),
+            _ => None,
+        };
+
+        assert_eq!(task("- [ ] szkic"), Some((false, "szkic".to_string())));
+        assert_eq!(task("* [x] wydanie"), Some((true, "wydanie".to_string())));
+        assert_eq!(task("- [X] testy"), Some((true, "testy".to_string())));
+        assert_eq!(task("- [-] w toku"), None);
+        assert_eq!(task("- [x]bez spacji"), None);
+    }
+
+    const FUZZ_PREFIXES: &[&str] = &[
+        "",
+        "",
+        "#",
+        "## ",
+        "- ",
+        "* ",
+        "> ",
+        ">>",
+        "- [ ] ",
+        "- [x]",
+        "* [X] ",
+        "- [-] ",
+        "@instant ",
+        "@instant",
+        "@frame-title ",
+        "@theme ",
+        "@banner-heading",
+        "@space ",
+        "```",
+        "```rust ",
+        "---",
+        "–",
+        "===",
+        "  ",
+    ];
+    const FUZZ_WORDS: &[&str] = &[
+        "ramka",
+        "neon",
+        "",
+        "#",
+        "-",
+        ">",
+        "[x]",
+        "[ ]",
+        "@instant",
+        "@space 2",
+        "@theme",
+        "\\",
+        "\\\\",
+        "–––",
+        "zażółć",
+        "🚀",
+        "7",
+    ];
+    const FUZZ_SUFFIXES: &[&str] = &["", "", "", " \\", "\\", "\\\\", "  "];
+
+    fn fuzz_script(seed: u64) -> String {
+        let mut rng = crate::demo::Lcg(seed);
+        let mut pick = |options: &[&'static str]| options[rng.below(options.len() as u64) as usize];
+        let mut script = String::new();
+        for _ in 0..24 {
+            script.push_str(pick(FUZZ_PREFIXES));
+            for _ in 0..3 {
+                script.push_str(pick(FUZZ_WORDS));
+                script.push_str(pick(&[" ", "", "  "]));
+            }
+            script.push_str(pick(FUZZ_SUFFIXES));
+            script.push('\n');
+        }
+        script
+    }
+
+    /// Zapisuje sekwencje z powrotem w postaci kanonicznej, którą parser
+    /// odczytuje bez zmian. Normalizacja względem oryginału: poziomy `#`
+    /// i `*` zamiast `-` giną, separator to zawsze `---`, `[X]` staje się
+    /// `[x]`, białe znaki na krańcach linii znikają, a końcowy `\` tekstu
+    /// dostaje drugi `\`, żeby nie sklejał linii. Dyrektywy trafiają do
+    /// osobnych linii, poza `@instant`, który zostaje w linii, gdy treść
+    /// zaczyna się od nazwy innej dyrektywy. Z opisu po płotku bloku kodu
+    /// zostaje tylko język, a zamykający płotek to zawsze trzy znaki `.
+    /// Akcenty wracają jako znaczniki Markdown. Reguły `--rules` nie biorą
+    /// udziału (wymagają pliku reguł).
+    fn write_canonical(segments: &[Segment]) -> String {
+        let inline_text = |spans: &[Span]| -> String {
+            spans
+                .iter()
+                .map(|span| {
+                    let style = span.style();
+                    let mut text = span.text().to_string();
+                    if style.color == Some(ColorSlot::Dim) {
+                        text = format!("`{}`", text);
+                    }
+                    if style.italic {
+                        text = format!("*{}*", text);
+                    }
+                    if style.bold {
+                        text = format!("**{}**", text);
+                    }
+                    text
+                })
+                .collect()
+        };
+        let escaped = |mut line: String| {
+            if line.ends_with('\\') {
+                line.push('\\');
+            }
+            line
+        };
+        let mut script = String::new();
+        for segment in segments {
+            if let Some(title) = segment.frame_title() {
+                let line = escaped(format!("{} {}", FRAME_TITLE_DIRECTIVE, title));
+                script.push_str(&format!("{}\n", line));
+            }
+            if let Some(theme) = segment.theme() {
+                let line = escaped(format!("{} {}", THEME_DIRECTIVE, theme));
+                script.push_str(&format!("{}\n", line));
+            }
+            if segment.banner_heading {
+                script.push_str(&format!("{}\n", BANNER_HEADING_DIRECTIVE));
+            }
+            if let Some(budget) = segment.budget() {
+                script.push_str(&format!("{} {}s\n", BUDGET_DIRECTIVE, budget.as_secs()));
+            }
+            let line = match segment.kind() {
+                SegmentKind::Heading(spans) => format!(
+                    "{} {}",
+                    "#".repeat(segment.heading_level),
+                    inline_text(spans)
+                ),
+                SegmentKind::Bullet { text, .. } => format!("- {}", inline_text(text)),
+                SegmentKind::Numbered { number, text } => {
+                    format!("{}. {}", number, inline_text(text))
+                }
+                SegmentKind::Callout(spans) => format!("> {}", inline_text(spans)),
+                SegmentKind::Quote(rows) => rows
+                    .iter()
+                    .map(|spans| format!("> {}", inline_text(spans)))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                SegmentKind::Table { headers, rows } => std::iter::once(headers)
+                    .chain(rows)
+                    .map(|cells| {
+                        let cells: Vec<String> =
+                            cells.iter().map(|cell| inline_text(cell)).collect();
+                        format!("| {} |", cells.join(" | "))
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                SegmentKind::Contents { title, slide } => {
+                    format!("{} {} {:02}", inline_text(title), CONTENTS_LEADER, slide)
+                }
+                SegmentKind::Plain(spans) => inline_text(spans),
+                SegmentKind::Task { done, text } => {
+                    format!(
+                        "- [{}] {}",
+                        if *done { 'x' } else { ' ' },
+                        inline_text(text)
+                    )
+                }
+                SegmentKind::Separator => "===".to_string(),
+                SegmentKind::SlideBreak => "---".to_string(),
+                SegmentKind::Code { language, lines } => {
+                    let fence = format!("{}{}", CODE_FENCE, language.as_deref().unwrap_or(""));
+                    let mut block = format!("{}\n", escaped(fence));
+                    for line in lines {
+                        block.push_str(line);
+                        block.push('\n');
+                    }
+                    block.push_str(CODE_FENCE);
+                    block
+                }
+                SegmentKind::Spacer(rows) => format!("{} {}", SPACE_DIRECTIVE, rows),
+                SegmentKind::Notes(lines) => format!("{}\n{}", NOTES_MARKER, lines.join("\n")),
+                SegmentKind::Custom { .. } => unreachable!("reguły nie biorą udziału w teście"),
+            }
+            .trim()
+            .to_string();
+            let line = escaped(line);
+            if segment.instant() {
+                let separator = if line.is_empty() { "\n" } else { " " };
+                script.push_str(INSTANT_DIRECTIVE);
+                script.push_str(separator);
+            }
+            script.push_str(&line);
+            script.push('\n');
+        }
+        script
+    }
+
+    #[test]
+    fn canonical_script_round_trips_through_parser() {
+        let rules = RuleSet::default();
+        for seed in 0..200 {
+            let script = fuzz_script(seed);
+            let first = parse_segments(script.as_bytes(), &rules).unwrap();
+            let canonical = write_canonical(&first);
+            let second = parse_segments(canonical.as_bytes(), &rules).unwrap();
+
+            assert_eq!(
+                first.len(),
+                second.len(),
+                "ziarno {seed}:\n{script}\n--\n{canonical}"
+            );
+            assert_eq!(
+                write_canonical(&second),
+                canonical,
+                "ziarno {seed}:\n{script}"
+            );
+        }
+    }
+
+    #[test]
+    fn dashes_split_deck_into_slides() {
+        let slides = load_deck(
+            &[PathBuf::from("tests/fixtures/three_slides.txt")],
+            JoinMode::Contiguous,
+            false,
+            false,
+            &RuleSet::default(),
+        )
+        .unwrap();
+
+        assert_eq!(slides.len(), 3);
+        assert_eq!(slides[0].frame_title(), Some("Wstęp"));
+        assert!(
+            slides[0]
+                .segments()
+                .iter()
+                .any(|segment| matches!(segment.kind(), SegmentKind::Separator))
+        );
+        assert_eq!(slides[1].segments().len(), 3);
+        assert_eq!(
+            slides[2].segments()[0].heading().as_deref(),
+            Some("Podsumowanie")
+        );
+    }
+
+    #[test]
+    fn alignment_markers_place_text_within_the_frame() {
+        let config = Config::builder()
+            .animations(false)
+            .frame_width(40)
+            .build()
+            .unwrap();
+        let escapes = regex::Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]").unwrap();
+        let row = |line: &str| {
+            let segment = classify_segment(line, &RuleSet::default());
+            let mut out = Vec::new();
+            animate_line(&mut out, &config, 0, &segment, false).unwrap();
+            let row = escapes
+                .replace_all(&String::from_utf8(out).unwrap(), "")
+                .trim_end()
+                .to_string();
+            assert_eq!(row.chars().count(), 40);
+            row
+        };
+        let column = |row: &str, text: &str| row[..row.find(text).unwrap()].chars().count();
+
+        let centered = row(":^: # ABC");
+        assert_eq!(column(&centered, "ABC"), 9 + 13);
+        assert_eq!(column(&row(":^:# ABCD"), "ABCD"), 9 + 13);
+        assert_eq!(column(&row(":>: — autor"), "— autor"), 39 - 7);
+        assert_eq!(column(&row(":<: lewo"), "lewo"), 9);
+        assert_eq!(column(&row("# ABC"), "ABC"), 9);
+    }
+
+    #[test]
+    fn nested_bullets_indent_markers_and_keep_the_border() {
+        let config = Config::builder()
+            .animations(false)
+            .frame_width(40)
+            .no_color(true)
+            .build()
+            .unwrap();
+        let row = |line: &str| {
+            let segment = classify_segment(line, &RuleSet::default());
+            let mut out = Vec::new();
+            animate_line(&mut out, &config, 0, &segment, false).unwrap();
+            let row = String::from_utf8(out).unwrap().trim_end().to_string();
+            assert_eq!(visible_width(&row), 40, "{row}");
+            row
+        };
+        let column = |row: &str, glyph: &str| row[..row.find(glyph).unwrap()].chars().count();
+
+        assert_eq!(column(&row("- punkt"), "• punkt"), 9);
+        assert_eq!(column(&row("  - podpunkt"), "◦ podpunkt"), 11);
+        assert_eq!(column(&row("\t\t* głębiej"), "▪ głębiej"), 13);
+        assert_eq!(column(&row("            - dno"), "◦ dno"), 17);
+        assert!(row("    - bardzo długi podpunkt, który nie mieści się").ends_with("›│"));
+    }
+
+    #[test]
+    fn banner_heading_falls_back_to_brackets_without_color() {
+        let config = Config::builder()
+            .animations(false)
+            .frame_width(40)
+            .no_color(true)
+            .heading_banner(true)
+            .build()
+            .unwrap();
+        let segment = classify_segment("# Plan", &RuleSet::default());
+        let mut out = Vec::new();
+        animate_line(&mut out, &config, 0, &segment, false).unwrap();
+        let row = String::from_utf8(out).unwrap().trim_end().to_string();
+
+        assert_eq!(visible_width(&row), 40, "{row}");
+        assert_eq!(
+            row,
+            format!("│{}[ PLAN ]{}│", " ".repeat(15), " ".repeat(15))
+        );
+    }
+
+    #[test]
+    fn consecutive_quote_lines_form_one_wrapped_box() {
+        let config = Config::builder()
+            .animations(false)
+            .frame_width(40)
+            .no_color(true)
+            .build()
+            .unwrap();
+        let slides = load_deck(
+            &[PathBuf::from("tests/fixtures/quote.txt")],
+            JoinMode::Contiguous,
+            false,
+            false,
+            &RuleSet::default(),
+        )
+        .unwrap();
+        let segments = slides[0].segments();
+        let SegmentKind::Quote(rows) = segments[1].kind() else {
+            panic!("{:?}", segments[1].kind());
+        };
+        assert_eq!(rows.len(), 3);
+        assert_eq!(plain_text(&rows[1]), "");
+        assert!(matches!(segments[3].kind(), SegmentKind::Callout(_)));
+
+        let mut out = Vec::new();
+        animate_line(&mut out, &config, 0, &segments[1], false).unwrap();
+        let output = String::from_utf8(out).unwrap();
+        let drawn: Vec<&str> = output.lines().collect();
+        assert!(drawn.len() > rows.len());
+        for row in &drawn {
+            assert_eq!(visible_width(row), 40, "{row}");
+            assert!(row.contains("▌"), "{row}");
+        }
+    }
+
+    #[test]
+    fn task_items_pad_by_checkbox_width_and_color_by_state() {
+        let slides = load_deck(
+            &[PathBuf::from("tests/fixtures/tasks.txt")],
+            JoinMode::Contiguous,
+            false,
+            false,
+            &RuleSet::default(),
+        )
+        .unwrap();
+        let segments = slides[0].segments();
+        let done: Vec<bool> = segments
+            .iter()
+            .filter_map(|segment| match segment.kind() {
+                SegmentKind::Task { done, .. } => Some(*done),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(done, [false, true, true]);
+
+        let render = |config: &Config, segment: &Segment| {
+            let mut out = Vec::new();
+            animate_line(&mut out, config, 0, segment, false).unwrap();
+            String::from_utf8(out).unwrap().trim_end().to_string()
+        };
+        let plain = Config::builder()
+            .animations(false)
+            .frame_width(40)
+            .no_color(true)
+            .build()
+            .unwrap();
+        for segment in &segments[1..4] {
+            let row = render(&plain, segment);
+            assert_eq!(visible_width(&row), 40, "{row}");
+        }
+        assert!(render(&plain, &segments[1]).contains("☐ szkic slajdów"));
+        assert!(render(&plain, &segments[2]).contains("☑ wybór motywu"));
+
+        let colored = Config::builder()
+            .animations(false)
+            .frame_width(40)
+            .build()
+            .unwrap();
+        let open = render(&colored, &segments[1]);
+        let finished = render(&colored, &segments[2]);
+        assert!(
+            open.contains(&format!("{}☐", colored.color_dim())),
+            "{open:?}"
+        );
+        assert!(
+            finished.contains(&format!("{}☑", colored.color_glow())),
+            "{finished:?}"
+        );
+    }
+
+    #[test]
+    fn pipe_rows_form_a_boxed_table_within_the_frame() {
+        let config = Config::builder()
+            .animations(false)
+            .frame_width(40)
+            .no_color(true)
+            .build()
+            .unwrap();
+        let slides = load_deck(
+            &[PathBuf::from("tests/fixtures/table.txt")],
+            JoinMode::Contiguous,
+            false,
+            false,
+            &RuleSet::default(),
+        )
+        .unwrap();
+        let segments = slides[0].segments();
+        let SegmentKind::Table { headers, rows } = segments[1].kind() else {
+            panic!("{:?}", segments[1].kind());
+        };
+        assert_eq!(headers.len(), 3);
+        assert_eq!(rows.len(), 2);
+        assert!(matches!(
+            slides[1].segments()[0].kind(),
+            SegmentKind::Plain(_)
+        ));
+
+        let mut out = Vec::new();
+        animate_line(&mut out, &config, 0, &segments[1], false).unwrap();
+        let output = String::from_utf8(out).unwrap();
+        let drawn: Vec<&str> = output.lines().collect();
+        assert_eq!(drawn.len(), 6);
+        for row in &drawn {
+            assert_eq!(visible_width(row), 40, "{row}");
+        }
+        assert!(drawn[0].contains("┌───"));
+        assert!(drawn[3].contains("›"));
+    }
+
+    #[test]
+    fn hyperlinks_wrap_only_the_label_in_osc_8() {
+        let segment = classify_segment("- [docs](https://docs.rs) tutaj", &RuleSet::default());
+        let row = |hyperlinks: bool| {
+            let config = Config::builder()
+                .animations(false)
+                .frame_width(40)
+                .hyperlinks(hyperlinks)
+                .build()
+                .unwrap();
+            let mut out = Vec::new();
+            animate_line(&mut out, &config, 0, &segment, false).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let linked = row(true);
+        assert!(linked.contains("\x1b]8;;https://docs.rs\x07docs"));
+        assert!(linked.contains("docs\x1b]8;;\x07"));
+        assert!(!row(false).contains("\x1b]8"));
+        assert_eq!(visible_width(&linked), visible_width(&row(false)));
+        assert_eq!(visible_width(linked.trim_end()), 40);
+    }
+
+    #[test]
+    fn numbered_items_are_renumbered_within_a_slide() {
+        let slides = load_deck(
+            &[PathBuf::from("tests/fixtures/numbered.txt")],
+            JoinMode::Contiguous,
+            false,
+            false,
+            &RuleSet::default(),
+        )
+        .unwrap();
+        let numbers = |slide: &Slide| -> Vec<Option<usize>> {
+            slide
+                .segments()
+                .iter()
+                .map(|segment| match segment.kind() {
+                    SegmentKind::Numbered { number, .. } => Some(*number),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            numbers(&slides[0]),
+            [
+                None,
+                None,
+                Some(1),
+                Some(2),
+                Some(3),
+                None,
+                Some(3),
+                Some(4)
+            ]
+        );
+        assert_eq!(numbers(&slides[1]), [None, None, None]);
+
+        let config = Config::builder()
+            .animations(false)
+            .no_color(true)
+            .build()
+            .unwrap();
+        let mut out = Vec::new();
+        animate_line(&mut out, &config, 0, &slides[0].segments()[3], false).unwrap();
+        assert!(
+            String::from_utf8(out)
+                .unwrap()
+                .contains(":: 2. drugi krok ")
+        );
+    }
+
+    #[test]
+    fn notes_marker_moves_the_rest_of_the_slide_into_notes() {
+        let slides = load_deck(
+            &[PathBuf::from("tests/fixtures/notes.txt")],
+            JoinMode::Contiguous,
+            false,
+            false,
+            &RuleSet::default(),
+        )
+        .unwrap();
+
+        assert_eq!(slides.len(), 2);
+        assert_eq!(slides[0].segments().len(), 2);
+        assert_eq!(
+            slides[0].notes(),
+            ["Przywitać się z salą.", "Wspomnieć o RustLab."]
+        );
+        assert_eq!(slides[1].segments().len(), 2);
+        assert!(slides[1].notes().is_empty());
+    }
+
+    #[test]
+    fn budget_directive_accepts_seconds_and_minutes() {
+        assert_eq!(parse_budget("@budget 90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_budget("  @budget 2m"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_budget("@budget 45"), Some(Duration::from_secs(45)));
+        assert_eq!(parse_budget("@budget"), None);
+        assert_eq!(parse_budget("@budget 0s"), None);
+        assert_eq!(parse_budget("@budget długo"), None);
+        assert_eq!(parse_budget("@budgety 5"), None);
+    }
+
+    #[test]
+    fn custom_rules_take_precedence_over_builtin_classification() {
+        let rules = rules::load_from_path(Path::new("tests/fixtures/rules.toml")).unwrap();
+        let input = "TODO: dopisać demo\n- punkt\n# Nagłówek\n";
+        let segments = parse_segments(input.as_bytes(), &rules).unwrap();
+
+        assert_eq!(segments.len(), 3);
+        let SegmentKind::Custom { style, text } = segments[0].kind() else {
+            panic!("{:?}", segments[0].kind());
+        };
+        assert_eq!(plain_text(text), "dopisać demo");
+        assert_eq!(style.color(), ColorSlot::Glow);
+        assert!(style.bold() && style.underline() && !style.italic());
+        assert_eq!(style.marker(), Some("⚑"));
+        assert!(matches!(segments[1].kind(), SegmentKind::Bullet { .. }));
+        assert!(matches!(segments[2].kind(), SegmentKind::Heading(_)));
+
+        let config = Config::builder()
+            .animations(false)
+            .frame_width(40)
+            .build()
+            .unwrap();
+        let mut out = Vec::new();
+        animate_line(&mut out, &config, 0, &segments[0], false).unwrap();
+        let row = String::from_utf8(out).unwrap();
+        assert!(
+            row.contains(&format!(
+                "{}{}{}⚑ ",
+                config.style(BOLD),
+                config.style(UNDERLINE),
+                config.color_glow()
+            )),
+            "{row:?}"
+        );
+        assert!(row.contains("dopisać demo"), "{row:?}");
+    }
+
+    #[test]
+    fn segment_parser_yields_segments_lazily() {
+        let rules = RuleSet::default();
+        let endless = BufReader::new(io::repeat(b'\n'));
+        let parser = SegmentParser::new(endless, &rules);
+
+        assert_eq!(parser.take(3).filter_map(Result::ok).count(), 3);
+    }
+
+    #[test]
+    fn initial_frame_width_follows_the_terminal_within_bounds() {
+        assert_eq!(initial_frame_width(None), 120);
+        assert_eq!(initial_frame_width(Some(80)), 80);
+        assert_eq!(initial_frame_width(Some(250)), 120);
+        assert_eq!(initial_frame_width(Some(20)), 40);
+        assert_eq!(initial_frame_width(Some(0)), 120);
+    }
+}
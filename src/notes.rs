@@ -0,0 +1,133 @@
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+/// Zapis notatek prelegenta do pliku lub potoku nazwanego z osobnego wątku –
+/// otwarcie potoku bez czytelnika blokuje tylko ten wątek, a nie nawigację.
+pub(crate) struct NotesOut {
+    pages: Sender<String>,
+    errors: Receiver<String>,
+    warned: bool,
+}
+
+impl NotesOut {
+    pub(crate) fn spawn(path: PathBuf) -> Self {
+        let (pages, queued) = mpsc::channel::<String>();
+        let (failed, errors) = mpsc::channel();
+        thread::spawn(move || {
+            while let Ok(mut page) = queued.recv() {
+                while let Ok(newer) = queued.try_recv() {
+                    page = newer;
+                }
+                if let Err(error) = std::fs::write(&path, page) {
+                    let _ = failed.send(format!("{}: {}", path.display(), error));
+                }
+            }
+        });
+        Self {
+            pages,
+            errors,
+            warned: false,
+        }
+    }
+
+    pub(crate) fn write(&self, page: String) {
+        let _ = self.pages.send(page);
+    }
+
+    /// Pierwszy błąd zapisu; kolejne są pomijane, a zapis trwa dalej.
+    pub(crate) fn take_warning(&mut self) -> Option<String> {
+        let error = self.errors.try_recv().ok()?;
+        while self.errors.try_recv().is_ok() {}
+        (!std::mem::replace(&mut self.warned, true)).then_some(error)
+    }
+}
+
+pub(crate) fn page(number: usize, total: usize, elapsed: Duration, notes: &[String]) -> String {
+    let seconds = elapsed.as_secs();
+    let mut page = format!(
+        "SEQ :: {:03}/{:03}  CZAS :: {:02}:{:02}\n\n",
+        number,
+        total,
+        seconds / 60,
+        seconds % 60
+    );
+    if notes.is_empty() {
+        page.push_str("(brak notatek)\n");
+    }
+    for note in notes {
+        page.push_str(note);
+        page.push('\n');
+    }
+    page
+}
+
+/// Znacznik czasu `[m:ss]` na początku notatki: moment prezentacji, w którym
+/// prelegent powinien dojść do tej notatki, i tekst bez znacznika.
+pub(crate) fn cue(note: &str) -> Option<(Duration, &str)> {
+    let rest = note.trim_start().strip_prefix('[')?;
+    let (stamp, text) = rest.split_once(']')?;
+    let (minutes, seconds) = stamp.split_once(':')?;
+    if seconds.len() != 2
+        || !minutes
+            .bytes()
+            .chain(seconds.bytes())
+            .all(|byte| byte.is_ascii_digit())
+    {
+        return None;
+    }
+    let minutes: u64 = minutes.parse().ok()?;
+    let seconds: u64 = seconds.parse().ok().filter(|seconds| *seconds < 60)?;
+    Some((
+        Duration::from_secs(minutes * 60 + seconds),
+        text.trim_start(),
+    ))
+}
+
+/// Notatka, przy której prelegent powinien być po `elapsed` od startu:
+/// ostatnia ze znacznikiem czasu, który już minął.
+pub(crate) fn current_cue(notes: &[String], elapsed: Duration) -> Option<usize> {
+    notes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, note)| cue(note).map(|(at, _)| (index, at)))
+        .take_while(|(_, at)| *at <= elapsed)
+        .last()
+        .map(|(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cues_are_parsed_off_the_note_and_followed_by_elapsed_time() {
+        assert_eq!(
+            cue("[0:30] pokaz demo"),
+            Some((Duration::from_secs(30), "pokaz demo"))
+        );
+        assert_eq!(
+            cue("  [12:05]pytania"),
+            Some((Duration::from_secs(12 * 60 + 5), "pytania"))
+        );
+        assert_eq!(cue("[0:75] za dużo sekund"), None);
+        assert_eq!(cue("[a:30] litera"), None);
+        assert_eq!(cue("bez znacznika [0:30]"), None);
+
+        let notes: Vec<String> = [
+            "wstęp",
+            "[0:00] start",
+            "bez czasu",
+            "[1:00] demo",
+            "[2:30] koniec",
+        ]
+        .iter()
+        .map(|note| note.to_string())
+        .collect();
+        assert_eq!(current_cue(&notes, Duration::from_secs(59)), Some(1));
+        assert_eq!(current_cue(&notes, Duration::from_secs(60)), Some(3));
+        assert_eq!(current_cue(&notes, Duration::from_secs(600)), Some(4));
+        assert_eq!(current_cue(&notes[..1], Duration::from_secs(600)), None);
+    }
+}
@@ -0,0 +1,33 @@
+use std::path::Path;
+use std::time::Duration;
+
+pub(crate) fn load(path: &Path) -> Result<Vec<Duration>, String> {
+    let contents = std::fs::read_to_string(path).map_err(|error| {
+        format!(
+            "Log czasów ({}) nie został wczytany: {}",
+            path.display(),
+            error
+        )
+    })?;
+
+    contents
+        .lines()
+        .enumerate()
+        .map(|(number, line)| (number + 1, line.split('#').next().unwrap_or("").trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(number, line)| {
+            line.trim_end_matches('s')
+                .parse::<f64>()
+                .ok()
+                .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                .ok_or_else(|| {
+                    format!(
+                        "Log czasów ({}) zawiera niepoprawny wpis w linii {}: {}",
+                        path.display(),
+                        number,
+                        line
+                    )
+                })
+        })
+        .collect()
+}
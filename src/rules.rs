@@ -0,0 +1,136 @@
+use clap::ValueEnum;
+use regex::Regex;
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawRuleFile {
+    #[serde(default, rename = "rule")]
+    rules: Vec<RawRule>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawRule {
+    #[serde(default)]
+    name: Option<String>,
+    pattern: String,
+    #[serde(default)]
+    color: ColorSlot,
+    #[serde(default)]
+    style: Vec<StyleFlag>,
+    #[serde(default)]
+    marker: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorSlot {
+    #[default]
+    Accent,
+    Dim,
+    Glow,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum StyleFlag {
+    Bold,
+    Italic,
+    Underline,
+}
+
+#[derive(Debug, Clone, Default, Hash)]
+pub struct RuleStyle {
+    color: ColorSlot,
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    marker: Option<String>,
+}
+
+impl RuleStyle {
+    pub fn color(&self) -> ColorSlot {
+        self.color
+    }
+
+    pub fn bold(&self) -> bool {
+        self.bold
+    }
+
+    pub fn italic(&self) -> bool {
+        self.italic
+    }
+
+    pub fn underline(&self) -> bool {
+        self.underline
+    }
+
+    pub fn marker(&self) -> Option<&str> {
+        self.marker.as_deref()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    pattern: Regex,
+    style: RuleStyle,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    pub fn classify(&self, line: &str) -> Option<(RuleStyle, String)> {
+        self.rules.iter().find_map(|rule| {
+            let captures = rule.pattern.captures(line)?;
+            let text = captures
+                .get(1)
+                .or_else(|| captures.get(0))
+                .map(|value| value.as_str().trim().to_string())
+                .unwrap_or_default();
+            Some((rule.style.clone(), text))
+        })
+    }
+}
+
+pub fn load_from_path(path: &Path) -> Result<RuleSet, Box<dyn std::error::Error>> {
+    let contents = std::fs::read_to_string(path).map_err(|error| {
+        format!(
+            "Plik reguł ({}) nie został wczytany: {}",
+            path.display(),
+            error
+        )
+    })?;
+    let raw: RawRuleFile = toml::from_str(&contents)?;
+
+    let mut rules = Vec::with_capacity(raw.rules.len());
+    for (position, rule) in raw.rules.into_iter().enumerate() {
+        let label = rule
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("#{}", position + 1));
+        let pattern = Regex::new(&rule.pattern).map_err(|error| {
+            format!(
+                "Reguła {} w pliku {} ma nieprawidłowy wzorzec: {}",
+                label,
+                path.display(),
+                error
+            )
+        })?;
+
+        rules.push(Rule {
+            pattern,
+            style: RuleStyle {
+                color: rule.color,
+                bold: rule.style.contains(&StyleFlag::Bold),
+                italic: rule.style.contains(&StyleFlag::Italic),
+                underline: rule.style.contains(&StyleFlag::Underline),
+                marker: rule.marker,
+            },
+        });
+    }
+
+    Ok(RuleSet { rules })
+}
@@ -0,0 +1,340 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ab_glyph::{Font, FontArc, PxScale, point};
+use chrono::Local;
+use image::{Rgb, RgbImage};
+
+use crate::export::{StyledLine, quote_row, styled_line};
+use crate::interaction::Slide;
+use crate::span::{Span, char_width, fit_spans};
+use crate::table::table_lines;
+use crate::{Config, SegmentKind, code_row, theme};
+
+const FONT_SIZE: f32 = 18.0;
+const CELL_WIDTH: u32 = 11;
+const ROW_HEIGHT: u32 = 26;
+const PADDING: u32 = 28;
+const STROKE: u32 = 2;
+const BACKGROUND: Rgb<u8> = Rgb([0, 0, 0]);
+const FILE_TIMESTAMP: &str = "%Y%m%d-%H%M%S";
+const SYSTEM_FONTS: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf",
+    "/usr/share/fonts/TTF/DejaVuSansMono.ttf",
+    "/usr/share/fonts/dejavu-sans-mono-fonts/DejaVuSansMono.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationMono-Regular.ttf",
+    "C:\\Windows\\Fonts\\consola.ttf",
+];
+
+/// Zapisuje sekwencję `index` jako PNG z ramką i kolorami motywu; plik
+/// dostaje w nazwie numer sekwencji i znacznik czasu.
+pub(crate) fn write_png(
+    config: &Config,
+    slides: &[Slide],
+    index: usize,
+    directory: &Path,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let config = &config.with_colors();
+    let slide = &slides[index];
+    let themed = slide
+        .theme()
+        .and_then(Result::ok)
+        .map(|theme| config.themed(theme));
+    let config = themed.as_ref().unwrap_or(config);
+
+    fs::create_dir_all(directory)?;
+    let path = directory.join(format!(
+        "slide-{:03}-{}.png",
+        index + 1,
+        Local::now().format(FILE_TIMESTAMP)
+    ));
+    render_png(config, slide, index, slides.len(), &load_fonts()).save(&path)?;
+    Ok(path)
+}
+
+/// Pierwsza dostępna systemowa czcionka monospace, a za nią wbudowane
+/// Hack i Noto Emoji – na nich kończy się szukanie brakujących znaków.
+fn load_fonts() -> Vec<FontArc> {
+    let system = SYSTEM_FONTS
+        .iter()
+        .find_map(|path| FontArc::try_from_vec(fs::read(path).ok()?).ok());
+    let embedded = [
+        epaint_default_fonts::HACK_REGULAR,
+        epaint_default_fonts::NOTO_EMOJI_REGULAR,
+    ]
+    .into_iter()
+    .filter_map(|bytes| FontArc::try_from_slice(bytes).ok());
+    system.into_iter().chain(embedded).collect()
+}
+
+fn render_png(
+    config: &Config,
+    slide: &Slide,
+    index: usize,
+    total: usize,
+    fonts: &[FontArc],
+) -> RgbImage {
+    let columns = config.frame_width().saturating_sub(4);
+    let rows: usize = slide
+        .segments()
+        .iter()
+        .map(|segment| match segment.kind() {
+            SegmentKind::Spacer(rows) => *rows,
+            SegmentKind::Code { lines, .. } => lines.len(),
+            SegmentKind::Quote(rows) => rows.len(),
+            SegmentKind::Table { headers, rows } => table_lines(headers, rows, columns).len(),
+            _ => 1,
+        })
+        .sum();
+    let frame_width = config.frame_width() as u32 * CELL_WIDTH;
+    let frame_height = (rows.max(1) as u32 + 2) * ROW_HEIGHT;
+    let mut canvas = Canvas {
+        image: RgbImage::from_pixel(
+            frame_width + 2 * PADDING,
+            frame_height + ROW_HEIGHT + 2 * PADDING,
+            BACKGROUND,
+        ),
+        fonts,
+    };
+    let dim = rgb(config.color_dim());
+
+    canvas.fill(PADDING, PADDING, frame_width, STROKE, dim);
+    canvas.fill(
+        PADDING,
+        PADDING + frame_height - STROKE,
+        frame_width,
+        STROKE,
+        dim,
+    );
+    canvas.fill(PADDING, PADDING, STROKE, frame_height, dim);
+    canvas.fill(
+        PADDING + frame_width - STROKE,
+        PADDING,
+        STROKE,
+        frame_height,
+        dim,
+    );
+    if let Some(title) = slide.frame_title() {
+        let fitted = fit_spans(&[Span::plain(title)], columns.saturating_sub(4));
+        canvas.fill(
+            PADDING + CELL_WIDTH,
+            PADDING,
+            (fitted.width as u32 + 2) * CELL_WIDTH,
+            STROKE,
+            BACKGROUND,
+        );
+        let line = StyledLine::new(fitted.spans, config.color_glow());
+        canvas.line(
+            config,
+            &line,
+            usize::MAX,
+            PADDING + 2 * CELL_WIDTH,
+            title_baseline(),
+        );
+    }
+
+    let mut row = 0;
+    for segment in slide.segments() {
+        match segment.kind() {
+            SegmentKind::Spacer(rows) => {
+                row += rows;
+                continue;
+            }
+            SegmentKind::Code { lines, .. } => {
+                for (offset, line) in lines.iter().enumerate() {
+                    let fitted = code_row(line, columns);
+                    let line = StyledLine::new(fitted.spans, config.color_dim());
+                    canvas.line(
+                        config,
+                        &line,
+                        usize::MAX,
+                        column_x(0),
+                        row_baseline(row + offset),
+                    );
+                }
+                row += lines.len();
+                continue;
+            }
+            SegmentKind::Quote(rows) => {
+                for (offset, spans) in rows.iter().enumerate() {
+                    let line = StyledLine {
+                        italic: true,
+                        ..StyledLine::new(quote_row(spans), config.color_glow())
+                    };
+                    canvas.line(
+                        config,
+                        &line,
+                        columns,
+                        column_x(0),
+                        row_baseline(row + offset),
+                    );
+                }
+                row += rows.len();
+                continue;
+            }
+            SegmentKind::Table { headers, rows } => {
+                let lines = table_lines(headers, rows, columns);
+                for (offset, line) in lines.iter().enumerate() {
+                    let line = StyledLine::new(line.clone(), config.color_accent());
+                    canvas.line(
+                        config,
+                        &line,
+                        columns,
+                        column_x(0),
+                        row_baseline(row + offset),
+                    );
+                }
+                row += lines.len();
+                continue;
+            }
+            SegmentKind::Separator | SegmentKind::SlideBreak => canvas.fill(
+                column_x(0),
+                row_baseline(row) - FONT_SIZE as u32 / 3,
+                columns as u32 * CELL_WIDTH,
+                STROKE,
+                dim,
+            ),
+            kind => {
+                let line = styled_line(config, kind);
+                canvas.line(config, &line, columns, column_x(0), row_baseline(row));
+            }
+        }
+        row += 1;
+    }
+
+    let footer = format!("{} :: {}/{}", config.presentation_title(), index + 1, total);
+    let height = canvas.image.height();
+    canvas.line(
+        config,
+        &StyledLine::new(vec![Span::plain(footer)], config.color_dim()),
+        usize::MAX,
+        PADDING,
+        height - PADDING,
+    );
+    canvas.image
+}
+
+struct Canvas<'a> {
+    image: RgbImage,
+    fonts: &'a [FontArc],
+}
+
+impl Canvas<'_> {
+    fn fill(&mut self, x: u32, y: u32, width: u32, height: u32, color: Rgb<u8>) {
+        for py in y..(y + height).min(self.image.height()) {
+            for px in x..(x + width).min(self.image.width()) {
+                self.image.put_pixel(px, py, color);
+            }
+        }
+    }
+
+    /// Kursywa nie ma osobnego kroju, więc pozostaje prosta; pogrubienie
+    /// powstaje przez dwukrotne nałożenie znaku z przesunięciem o piksel.
+    fn line(&mut self, config: &Config, line: &StyledLine, columns: usize, x: u32, baseline: u32) {
+        let fitted = fit_spans(&line.spans, columns);
+        let base = rgb(line.color);
+        let mut column = 0;
+        for span in &fitted.spans {
+            let style = span.style();
+            let color = style.color.map_or(base, |slot| rgb(config.color(slot)));
+            let start = column;
+            for ch in span.text().chars() {
+                self.glyph(
+                    ch,
+                    x + column as u32 * CELL_WIDTH,
+                    baseline,
+                    color,
+                    line.bold || style.bold,
+                );
+                column += char_width(ch);
+            }
+            let run = (column - start) as u32 * CELL_WIDTH;
+            let run_x = x + start as u32 * CELL_WIDTH;
+            if line.underline || style.underline {
+                self.fill(run_x, baseline + 3, run, 1, color);
+            }
+        }
+        if fitted.truncated {
+            self.glyph('›', x + column as u32 * CELL_WIDTH, baseline, base, false);
+        }
+    }
+
+    fn glyph(&mut self, ch: char, x: u32, baseline: u32, color: Rgb<u8>, bold: bool) {
+        let Some(font) = self.fonts.iter().find(|font| font.glyph_id(ch).0 != 0) else {
+            return;
+        };
+        let glyph = font
+            .glyph_id(ch)
+            .with_scale_and_position(PxScale::from(FONT_SIZE), point(x as f32, baseline as f32));
+        let Some(outline) = font.outline_glyph(glyph) else {
+            return;
+        };
+        let bounds = outline.px_bounds();
+        let image = &mut self.image;
+        for offset in 0..if bold { 2 } else { 1 } {
+            outline.draw(|gx, gy, coverage| {
+                let px = bounds.min.x as i64 + i64::from(gx) + offset;
+                let py = bounds.min.y as i64 + i64::from(gy);
+                if px < 0
+                    || py < 0
+                    || px >= i64::from(image.width())
+                    || py >= i64::from(image.height())
+                {
+                    return;
+                }
+                let pixel = image.get_pixel_mut(px as u32, py as u32);
+                for channel in 0..3 {
+                    let blended = f32::from(pixel.0[channel]) * (1.0 - coverage)
+                        + f32::from(color.0[channel]) * coverage;
+                    pixel.0[channel] = blended.round() as u8;
+                }
+            });
+        }
+    }
+}
+
+fn rgb(escape: &str) -> Rgb<u8> {
+    let (red, green, blue) = theme::escape_rgb(escape).unwrap_or((0xff, 0xaf, 0x00));
+    Rgb([red, green, blue])
+}
+
+fn column_x(column: usize) -> u32 {
+    PADDING + (column as u32 + 2) * CELL_WIDTH
+}
+
+fn row_baseline(row: usize) -> u32 {
+    PADDING + (row as u32 + 1) * ROW_HEIGHT + FONT_SIZE as u32
+}
+
+fn title_baseline() -> u32 {
+    PADDING + FONT_SIZE as u32 / 3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::RuleSet;
+    use crate::{Segment, SegmentParser};
+
+    #[test]
+    fn renders_framed_slide_in_theme_colors() {
+        let config = Config::builder().frame_width(40).build().unwrap();
+        let rules = RuleSet::default();
+        let script = "@frame-title Migawka\n# Nagłówek\n- punkt 🚀\n---\n- [x] zrobione\n";
+        let segments: Vec<Segment> = SegmentParser::new(script.as_bytes(), &rules)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let slide = Slide::new(segments, "migawka.txt", None);
+        let image = render_png(&config, &slide, 0, 1, &load_fonts());
+
+        assert_eq!(image.width(), 40 * CELL_WIDTH + 2 * PADDING);
+        assert_eq!(image.height(), 6 * ROW_HEIGHT + ROW_HEIGHT + 2 * PADDING);
+        assert_eq!(
+            *image.get_pixel(PADDING, PADDING + ROW_HEIGHT),
+            rgb(config.color_dim())
+        );
+        for color in [config.color_glow(), config.color_accent()] {
+            assert!(image.pixels().any(|pixel| *pixel == rgb(color)));
+        }
+    }
+}
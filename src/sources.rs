@@ -0,0 +1,96 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+
+const EXTENSIONS: [&str; 2] = ["txt", "md"];
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
+#[clap(rename_all = "kebab_case")]
+pub(crate) enum SortMode {
+    #[default]
+    Name,
+    Mtime,
+    Numeric,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ScriptSources {
+    pub(crate) scripts: Vec<PathBuf>,
+    pub(crate) skipped: Vec<PathBuf>,
+}
+
+pub(crate) fn expand(paths: &[PathBuf], sort: SortMode) -> io::Result<ScriptSources> {
+    let mut sources = ScriptSources::default();
+    for path in paths {
+        if !path.is_dir() {
+            sources.scripts.push(path.clone());
+            continue;
+        }
+
+        let mut eligible = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let file = entry.path();
+            if !file.is_file() {
+                continue;
+            }
+            if is_eligible(&file) {
+                eligible.push(file);
+            } else {
+                sources.skipped.push(file);
+            }
+        }
+
+        if eligible.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Katalog {} nie zawiera plików .txt ani .md", path.display()),
+            ));
+        }
+
+        sort_scripts(&mut eligible, sort);
+        sources.scripts.extend(eligible);
+    }
+    sources.skipped.sort();
+    Ok(sources)
+}
+
+pub(crate) fn is_eligible(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            EXTENSIONS
+                .iter()
+                .any(|allowed| extension.eq_ignore_ascii_case(allowed))
+        })
+}
+
+fn sort_scripts(scripts: &mut [PathBuf], sort: SortMode) {
+    match sort {
+        SortMode::Name => scripts.sort(),
+        SortMode::Mtime => scripts.sort_by_key(|path| {
+            (
+                fs::metadata(path).and_then(|meta| meta.modified()).ok(),
+                path.clone(),
+            )
+        }),
+        SortMode::Numeric => scripts.sort_by(|left, right| {
+            match (numeric_prefix(left), numeric_prefix(right)) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+            .then_with(|| left.cmp(right))
+        }),
+    }
+}
+
+fn numeric_prefix(path: &Path) -> Option<u64> {
+    let name = path.file_name()?.to_str()?;
+    let digits: String = name.chars().take_while(char::is_ascii_digit).collect();
+    digits.parse().ok()
+}
@@ -0,0 +1,636 @@
+use regex::Regex;
+use unicode_width::UnicodeWidthChar;
+
+use crate::rules::ColorSlot;
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub(crate) struct SpanStyle {
+    pub(crate) bold: bool,
+    pub(crate) italic: bool,
+    pub(crate) underline: bool,
+    pub(crate) color: Option<ColorSlot>,
+}
+
+impl SpanStyle {
+    pub(crate) fn is_plain(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub(crate) struct Span {
+    text: String,
+    style: SpanStyle,
+    link: Option<String>,
+}
+
+impl Span {
+    pub(crate) fn plain(text: impl Into<String>) -> Self {
+        Self::styled(text, SpanStyle::default())
+    }
+
+    pub(crate) fn styled(text: impl Into<String>, style: SpanStyle) -> Self {
+        Self {
+            text: text.into(),
+            style,
+            link: None,
+        }
+    }
+
+    fn linked(mut self, link: Option<&str>) -> Self {
+        self.link = link.map(str::to_string);
+        self
+    }
+
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub(crate) fn style(&self) -> SpanStyle {
+        self.style
+    }
+
+    /// Adres odnośnika z `[etykieta](adres)` albo samego adresu `https://`.
+    pub(crate) fn link(&self) -> Option<&str> {
+        self.link.as_deref()
+    }
+
+    pub(crate) fn width(&self) -> usize {
+        self.text.chars().map(char_width).sum()
+    }
+
+    pub(crate) fn map_text(&self, map: impl FnOnce(&str) -> String) -> Self {
+        self.part(map(&self.text))
+    }
+
+    pub(crate) fn restyled(&self, map: impl FnOnce(SpanStyle) -> SpanStyle) -> Self {
+        Self::styled(self.text.clone(), map(self.style)).linked(self.link())
+    }
+
+    /// Fragment tekstu z tym samym stylem i odnośnikiem.
+    fn part(&self, text: impl Into<String>) -> Self {
+        Self::styled(text, self.style).linked(self.link())
+    }
+}
+
+pub(crate) fn char_width(ch: char) -> usize {
+    UnicodeWidthChar::width(ch).unwrap_or(0)
+}
+
+pub(crate) fn spans_width(spans: &[Span]) -> usize {
+    spans.iter().map(Span::width).sum()
+}
+
+pub(crate) fn plain_text(spans: &[Span]) -> String {
+    spans.iter().map(Span::text).collect()
+}
+
+pub(crate) fn uppercase(text: &str) -> String {
+    text.chars()
+        .map(|ch| {
+            let mut upper = ch.to_uppercase();
+            match (upper.next(), upper.next()) {
+                (Some(single), None) => single,
+                _ => ch,
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn highlight_spans(spans: &[Span], pattern: &Regex) -> Vec<Span> {
+    let mut highlighted = Vec::with_capacity(spans.len());
+    for span in spans {
+        let mut last = 0;
+        for found in pattern.find_iter(&span.text) {
+            if found.is_empty() {
+                continue;
+            }
+            if found.start() > last {
+                highlighted.push(span.part(&span.text[last..found.start()]));
+            }
+            highlighted.push(Span {
+                style: SpanStyle {
+                    bold: true,
+                    color: Some(ColorSlot::Glow),
+                    ..span.style
+                },
+                ..span.part(found.as_str())
+            });
+            last = found.end();
+        }
+        if last < span.text.len() || span.text.is_empty() {
+            highlighted.push(span.part(&span.text[last..]));
+        }
+    }
+    highlighted
+}
+
+/// Akcenty w stylu Markdown: `**pogrubienie**`, `*kursywa*` i `` `kod` ``
+/// (w kolorze `dim`, bez dalszego parsowania) oraz odnośniki
+/// `[etykieta](adres)` i same adresy `https://`. Znaczniki i adresy
+/// odnośników z etykietą znikają z tekstu, więc nie zajmują kolumn; znacznik
+/// bez pary zostaje zwykłym znakiem.
+pub(crate) fn parse_inline(text: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    push_inline(&mut spans, text, SpanStyle::default(), None);
+    if spans.is_empty() {
+        spans.push(Span::plain(""));
+    }
+    spans
+}
+
+fn push_inline(spans: &mut Vec<Span>, text: &str, style: SpanStyle, link: Option<&str>) {
+    let mut plain = String::new();
+    let mut rest = text;
+    let mut word_start = true;
+    while let Some(ch) = rest.chars().next() {
+        if let Some((marker, inner, after)) = emphasis(rest) {
+            if !plain.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut plain), style).linked(link));
+            }
+            match marker {
+                "`" => spans.push(
+                    Span::styled(
+                        inner,
+                        SpanStyle {
+                            color: Some(ColorSlot::Dim),
+                            ..style
+                        },
+                    )
+                    .linked(link),
+                ),
+                "**" => push_inline(
+                    spans,
+                    inner,
+                    SpanStyle {
+                        bold: true,
+                        ..style
+                    },
+                    link,
+                ),
+                _ => push_inline(
+                    spans,
+                    inner,
+                    SpanStyle {
+                        italic: true,
+                        ..style
+                    },
+                    link,
+                ),
+            }
+            rest = after;
+            word_start = false;
+            continue;
+        }
+        let found = markdown_link(rest).or_else(|| bare_url(rest).filter(|_| word_start));
+        if let Some((label, url, after)) = found.filter(|_| link.is_none()) {
+            if !plain.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut plain), style));
+            }
+            push_inline(spans, label, style, Some(url));
+            rest = after;
+            word_start = false;
+            continue;
+        }
+        plain.push(ch);
+        rest = &rest[ch.len_utf8()..];
+        word_start = !ch.is_alphanumeric();
+    }
+    if !plain.is_empty() {
+        spans.push(Span::styled(plain, style).linked(link));
+    }
+}
+
+/// `[etykieta](adres)` na początku `text`: etykieta, adres i reszta tekstu.
+fn markdown_link(text: &str) -> Option<(&str, &str, &str)> {
+    let (label, rest) = text.strip_prefix('[')?.split_once("](")?;
+    let (url, after) = rest.split_once(')')?;
+    if label.is_empty() || url.is_empty() || url.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((label, url, after))
+}
+
+/// Sam adres `http(s)://` na początku `text` – do pierwszej spacji, bez
+/// kończącej zdanie interpunkcji; etykietą jest on sam.
+fn bare_url(text: &str) -> Option<(&str, &str, &str)> {
+    let scheme = ["https://", "http://"]
+        .into_iter()
+        .find(|scheme| text.starts_with(scheme))?;
+    let end = text.find(char::is_whitespace).unwrap_or(text.len());
+    let url = text[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')']);
+    if url.len() == scheme.len() {
+        return None;
+    }
+    Some((url, url, &text[url.len()..]))
+}
+
+/// Akcent zaczynający się na początku `text`: znacznik, treść i reszta za
+/// znacznikiem zamykającym. Jak w Markdown, treść `*` i `**` nie może
+/// zaczynać się ani kończyć spacją.
+fn emphasis(text: &str) -> Option<(&'static str, &str, &str)> {
+    let marker = ["`", "**", "*"]
+        .into_iter()
+        .find(|marker| text.starts_with(marker))?;
+    let body = &text[marker.len()..];
+    let close = if marker == "`" {
+        body.find('`').filter(|at| *at > 0)?
+    } else {
+        if body.starts_with(char::is_whitespace) || body.starts_with('*') {
+            return None;
+        }
+        body.char_indices().map(|(at, _)| at).find(|&at| {
+            let before = &body[..at];
+            at > 0
+                && body[at..].starts_with(marker)
+                && !body[at + marker.len()..].starts_with('*')
+                && !before.ends_with(char::is_whitespace)
+                && (marker == "**" || !before.ends_with('*'))
+        })?
+    };
+    Some((marker, &body[..close], &body[close + marker.len()..]))
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct FittedLine {
+    pub(crate) spans: Vec<Span>,
+    pub(crate) width: usize,
+    pub(crate) truncated: bool,
+}
+
+pub(crate) fn fit_spans(spans: &[Span], available: usize) -> FittedLine {
+    if spans_width(spans) <= available {
+        return FittedLine {
+            spans: spans
+                .iter()
+                .filter(|span| !span.text.is_empty())
+                .cloned()
+                .collect(),
+            width: spans_width(spans),
+            truncated: false,
+        };
+    }
+
+    let limit = available.saturating_sub(1);
+    let mut fitted = Vec::new();
+    let mut width = 0;
+    'spans: for span in spans {
+        let mut text = String::new();
+        for ch in span.text.chars() {
+            let ch_width = char_width(ch);
+            if width + ch_width > limit {
+                if !text.is_empty() {
+                    fitted.push(span.part(text));
+                }
+                break 'spans;
+            }
+            text.push(ch);
+            width += ch_width;
+        }
+        if !text.is_empty() {
+            fitted.push(span.part(text));
+        }
+    }
+
+    FittedLine {
+        spans: fitted,
+        width: width + usize::from(available > 0),
+        truncated: available > 0,
+    }
+}
+
+/// Łamie spany na wiersze na granicach słów. Pierwszy wiersz ma `available`
+/// kolumn, kolejne o `hang` mniej – wcięcie dopisuje wywołujący. Spacje na
+/// łamaniu znikają, a słowo dłuższe niż wiersz jest dzielone w dowolnym miejscu.
+pub(crate) fn wrap_spans(spans: &[Span], available: usize, hang: usize) -> Vec<FittedLine> {
+    if available == 0 {
+        return vec![fit_spans(spans, available)];
+    }
+    let hang = if hang < available { hang } else { 0 };
+    let chars: Vec<(char, &Span)> = spans
+        .iter()
+        .flat_map(|span| span.text.chars().map(move |ch| (ch, span)))
+        .collect();
+
+    let limit = |row_count: usize| {
+        if row_count > 1 {
+            available - hang
+        } else {
+            available
+        }
+    };
+
+    let mut rows: Vec<Vec<(char, &Span)>> = vec![Vec::new()];
+    let mut width = 0;
+    for token in chars.chunk_by(|left, right| (left.0 == ' ') == (right.0 == ' ')) {
+        let token_width: usize = token.iter().map(|(ch, _)| char_width(*ch)).sum();
+        let row_empty = rows.last().is_none_or(Vec::is_empty);
+        if token[0].0 == ' ' {
+            if row_empty && rows.len() > 1 {
+                continue;
+            }
+            if width + token_width > limit(rows.len()) {
+                rows.push(Vec::new());
+                width = 0;
+            } else {
+                rows.last_mut().unwrap().extend_from_slice(token);
+                width += token_width;
+            }
+            continue;
+        }
+        if width + token_width > limit(rows.len()) && !row_empty {
+            let row = rows.last_mut().unwrap();
+            while row.last().is_some_and(|(ch, _)| *ch == ' ') {
+                row.pop();
+            }
+            rows.push(Vec::new());
+            width = 0;
+        }
+        for &(ch, source) in token {
+            if width + char_width(ch) > limit(rows.len()) && width > 0 {
+                rows.push(Vec::new());
+                width = 0;
+            }
+            rows.last_mut().unwrap().push((ch, source));
+            width += char_width(ch);
+        }
+    }
+
+    rows.into_iter()
+        .map(|row| {
+            let mut spans: Vec<Span> = Vec::new();
+            for (ch, source) in row {
+                match spans.last_mut() {
+                    Some(span) if span.style == source.style && span.link == source.link => {
+                        span.text.push(ch)
+                    }
+                    _ => spans.push(source.part(ch)),
+                }
+            }
+            FittedLine {
+                width: spans_width(&spans),
+                spans,
+                truncated: false,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_ascii_cjk_and_emoji_in_columns() {
+        assert_eq!(Span::plain("rust").width(), 4);
+        assert_eq!(Span::plain("日本語").width(), 6);
+        assert_eq!(Span::plain("🚀").width(), 2);
+        assert_eq!(Span::plain("zażółć").width(), 6);
+        assert_eq!(Span::plain("").width(), 0);
+    }
+
+    #[test]
+    fn uppercases_only_single_character_mappings() {
+        assert_eq!(uppercase("zażółć gęślą"), "ZAŻÓŁĆ GĘŚLĄ");
+        assert_eq!(uppercase("straße"), "STRAßE");
+        assert_eq!(uppercase("日本語 rust"), "日本語 RUST");
+    }
+
+    #[test]
+    fn parses_inline_emphasis_without_markers() {
+        let spans = parse_inline("to **szybki** i *prosty* `cargo run`");
+        let text: Vec<(&str, SpanStyle)> = spans
+            .iter()
+            .map(|span| (span.text(), span.style()))
+            .collect();
+        let bold = SpanStyle {
+            bold: true,
+            ..SpanStyle::default()
+        };
+        let italic = SpanStyle {
+            italic: true,
+            ..SpanStyle::default()
+        };
+        let code = SpanStyle {
+            color: Some(ColorSlot::Dim),
+            ..SpanStyle::default()
+        };
+        assert_eq!(
+            text,
+            [
+                ("to ", SpanStyle::default()),
+                ("szybki", bold),
+                (" i ", SpanStyle::default()),
+                ("prosty", italic),
+                (" ", SpanStyle::default()),
+                ("cargo run", code),
+            ]
+        );
+        assert_eq!(spans_width(&spans), "to szybki i prosty cargo run".len());
+
+        let nested = parse_inline("**bardzo *ważne***");
+        assert_eq!(nested[0].style(), bold);
+        assert!(nested[1].style().bold && nested[1].style().italic);
+        assert_eq!(plain_text(&nested), "bardzo ważne");
+        assert_eq!(
+            plain_text(&parse_inline("`**dosłownie**`")),
+            "**dosłownie**"
+        );
+    }
+
+    #[test]
+    fn leaves_unpaired_or_spaced_markers_as_text() {
+        for text in [
+            "2 * 3 * 4",
+            "**otwarte",
+            "a * b",
+            "`",
+            "****",
+            "snake_case*",
+        ] {
+            let spans = parse_inline(text);
+            assert_eq!(plain_text(&spans), text);
+            assert!(spans.iter().all(|span| span.style().is_plain()), "{text}");
+        }
+    }
+
+    #[test]
+    fn links_labels_and_bare_urls() {
+        let spans =
+            parse_inline("zob. [**repo**](https://example.com/repo) i https://rust-lang.org.");
+        let parts: Vec<(&str, Option<&str>)> = spans
+            .iter()
+            .map(|span| (span.text(), span.link()))
+            .collect();
+        assert_eq!(
+            parts,
+            [
+                ("zob. ", None),
+                ("repo", Some("https://example.com/repo")),
+                (" i ", None),
+                ("https://rust-lang.org", Some("https://rust-lang.org")),
+                (".", None),
+            ]
+        );
+        assert!(spans[1].style().bold);
+        assert_eq!(
+            spans_width(&spans),
+            "zob. repo i https://rust-lang.org.".len()
+        );
+
+        for text in [
+            "[](a.pl)",
+            "[a](b c)",
+            "xhttps://a.pl",
+            "https://",
+            "[a] (b)",
+        ] {
+            let spans = parse_inline(text);
+            assert_eq!(plain_text(&spans), text);
+            assert!(spans.iter().all(|span| span.link().is_none()), "{text}");
+        }
+    }
+
+    #[test]
+    fn wrapping_and_truncation_keep_links() {
+        let spans = parse_inline("[bardzo długa etykieta](https://a.pl)");
+        let rows = wrap_spans(&spans, 10, 0);
+        assert!(rows.len() > 1);
+        assert!(
+            rows.iter()
+                .flat_map(|row| &row.spans)
+                .all(|span| span.link() == Some("https://a.pl"))
+        );
+        let fitted = fit_spans(&spans, 8);
+        assert_eq!(fitted.spans[0].link(), Some("https://a.pl"));
+    }
+
+    #[test]
+    fn sums_width_across_spans() {
+        let bold = SpanStyle {
+            bold: true,
+            ..SpanStyle::default()
+        };
+        let spans = vec![
+            Span::plain("a "),
+            Span::styled("日本", bold),
+            Span::plain(" 🚀"),
+        ];
+        assert_eq!(spans_width(&spans), 9);
+        assert_eq!(plain_text(&spans), "a 日本 🚀");
+    }
+
+    #[test]
+    fn keeps_lines_that_fit() {
+        let spans = vec![Span::plain("abc"), Span::plain("def")];
+        let fitted = fit_spans(&spans, 6);
+        assert!(!fitted.truncated);
+        assert_eq!(fitted.width, 6);
+        assert_eq!(fitted.spans, spans);
+    }
+
+    #[test]
+    fn reserves_a_column_for_the_marker_when_truncating() {
+        let spans = vec![Span::plain("abcdef")];
+        let fitted = fit_spans(&spans, 4);
+        assert!(fitted.truncated);
+        assert_eq!(plain_text(&fitted.spans), "abc");
+        assert_eq!(fitted.width, 4);
+    }
+
+    #[test]
+    fn breaks_long_unbroken_tokens_at_the_column_boundary() {
+        let url = format!("https://example.com/{}", "a".repeat(280));
+        for available in [1, 2, 10, 40, 80, 120, 299] {
+            let fitted = fit_spans(&[Span::plain(url.as_str())], available);
+            assert!(fitted.truncated);
+            assert_eq!(fitted.width, available);
+            assert_eq!(spans_width(&fitted.spans), available - 1);
+            assert!(url.starts_with(&plain_text(&fitted.spans)));
+        }
+        assert!(!fit_spans(&[Span::plain(url.as_str())], 300).truncated);
+    }
+
+    #[test]
+    fn never_splits_a_wide_glyph_at_the_boundary() {
+        let spans = vec![Span::plain("ab日本")];
+        let fitted = fit_spans(&spans, 4);
+        assert_eq!(plain_text(&fitted.spans), "ab");
+        assert_eq!(fitted.width, 3);
+        assert!(fitted.truncated);
+    }
+
+    #[test]
+    fn truncation_preserves_span_styles() {
+        let italic = SpanStyle {
+            italic: true,
+            ..SpanStyle::default()
+        };
+        let spans = vec![Span::plain("ab"), Span::styled("cdef", italic)];
+        let fitted = fit_spans(&spans, 5);
+        assert_eq!(
+            fitted.spans,
+            vec![Span::plain("ab"), Span::styled("cd", italic)]
+        );
+    }
+
+    #[test]
+    fn highlights_matches_without_changing_width() {
+        let pattern = Regex::new("(?i)rust").unwrap();
+        let spans = vec![Span::plain("Rust i RUST"), Span::plain(" 日本")];
+        let highlighted = highlight_spans(&spans, &pattern);
+        let glow = SpanStyle {
+            bold: true,
+            color: Some(ColorSlot::Glow),
+            ..SpanStyle::default()
+        };
+        assert_eq!(
+            highlighted,
+            vec![
+                Span::styled("Rust", glow),
+                Span::plain(" i "),
+                Span::styled("RUST", glow),
+                Span::plain(" 日本"),
+            ]
+        );
+        assert_eq!(spans_width(&highlighted), spans_width(&spans));
+    }
+
+    #[test]
+    fn zero_width_budget_yields_nothing() {
+        let fitted = fit_spans(&[Span::plain("abc")], 0);
+        assert!(fitted.spans.is_empty());
+        assert_eq!(fitted.width, 0);
+        assert!(!fitted.truncated);
+    }
+
+    #[test]
+    fn wraps_at_word_boundaries_leaving_room_for_the_hang() {
+        let bold = SpanStyle {
+            bold: true,
+            ..SpanStyle::default()
+        };
+        let spans = vec![Span::plain("• ala ma "), Span::styled("kota i psa", bold)];
+        let rows = wrap_spans(&spans, 10, 2);
+        assert_eq!(
+            rows.iter()
+                .map(|row| plain_text(&row.spans))
+                .collect::<Vec<_>>(),
+            ["• ala ma", "kota i", "psa"]
+        );
+        assert_eq!(rows[1].spans, vec![Span::styled("kota i", bold)]);
+        assert!(rows.iter().all(|row| !row.truncated));
+        assert_eq!(rows[0].width, 8);
+    }
+
+    #[test]
+    fn splits_words_longer_than_a_row() {
+        let rows = wrap_spans(&[Span::plain("ab abcdefghij")], 4, 0);
+        assert_eq!(
+            rows.iter()
+                .map(|row| plain_text(&row.spans))
+                .collect::<Vec<_>>(),
+            ["ab", "abcd", "efgh", "ij"]
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::OnceLock;
+
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+
+use crate::span::char_width;
+use crate::{Background, Config, RESET, SCROLL_MARKER};
+
+const DARK_THEME: &str = "base16-ocean.dark";
+const LIGHT_THEME: &str = "InspiredGitHub";
+
+struct Highlighting {
+    syntaxes: SyntaxSet,
+    themes: ThemeSet,
+}
+
+/// Zbiory składni i motywów syntect wczytywane raz, przy pierwszym bloku
+/// kodu z językiem – rozpakowanie ich trwa zauważalnie długo.
+fn highlighting() -> &'static Highlighting {
+    static HIGHLIGHTING: OnceLock<Highlighting> = OnceLock::new();
+    HIGHLIGHTING.get_or_init(|| Highlighting {
+        syntaxes: SyntaxSet::load_defaults_newlines(),
+        themes: ThemeSet::load_defaults(),
+    })
+}
+
+/// Koloruje wiersze bloku kodu kodami 24-bitowymi i przycina je do
+/// `available` kolumn jak `code_row`; `None` dla nieznanego języka.
+pub(crate) fn highlight(
+    config: &Config,
+    language: &str,
+    lines: &[String],
+    available: usize,
+) -> Option<Vec<String>> {
+    let highlighting = highlighting();
+    let syntax = highlighting.syntaxes.find_syntax_by_token(language)?;
+    let mut highlighter = HighlightLines::new(syntax, theme(highlighting, config.background()));
+
+    let mut rows = Vec::with_capacity(lines.len());
+    for line in lines {
+        let source = format!("{}\n", line);
+        let tokens = highlighter
+            .highlight_line(&source, &highlighting.syntaxes)
+            .ok()?;
+        let total: usize = line.chars().map(char_width).sum();
+        let limit = if total > available {
+            available.saturating_sub(1)
+        } else {
+            available
+        };
+
+        let mut row = String::new();
+        let mut width = 0;
+        'tokens: for (style, text) in tokens {
+            let color = style.foreground;
+            row.push_str(&format!("\x1b[38;2;{};{};{}m", color.r, color.g, color.b));
+            for ch in text.chars().filter(|ch| *ch != '\n') {
+                if width + char_width(ch) > limit {
+                    break 'tokens;
+                }
+                row.push(ch);
+                width += char_width(ch);
+            }
+        }
+        if total > available && available > 0 {
+            row.push_str(config.color_dim());
+            row.push_str(SCROLL_MARKER);
+        }
+        row.push_str(config.style(RESET));
+        rows.push(row);
+    }
+    Some(rows)
+}
+
+fn theme(highlighting: &Highlighting, background: Background) -> &Theme {
+    let name = match background {
+        Background::Dark => DARK_THEME,
+        Background::Light => LIGHT_THEME,
+    };
+    &highlighting.themes.themes[name]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visible_width;
+
+    #[test]
+    fn colors_known_languages_within_the_frame() {
+        let config = Config::builder().build().unwrap();
+        let lines = vec![
+            "fn main() {".to_string(),
+            "    let zażółć = \"bardzo długi napis\";".to_string(),
+        ];
+        let rows = highlight(&config, "rust", &lines, 20).unwrap();
+
+        assert!(rows[0].contains("\x1b[38;2;"));
+        assert_eq!(visible_width(&rows[0]), 11);
+        assert_eq!(visible_width(&rows[1]), 20);
+        assert!(rows[1].ends_with(&format!("{}{}", SCROLL_MARKER, config.style(RESET))));
+        assert!(highlight(&config, "nieznany", &lines, 20).is_none());
+    }
+}
@@ -0,0 +1,170 @@
+use crate::rules::ColorSlot;
+use crate::span::{Span, SpanStyle, fit_spans, parse_inline, plain_text, spans_width};
+
+const MIN_COLUMN_WIDTH: usize = 1;
+/// Spacje po obu stronach komórki i kreska zamykająca: ` a │`.
+const CELL_CHROME: usize = 3;
+
+/// Komórki wiersza `| a | b |` – linia zaczyna się i kończy pionową kreską.
+pub(crate) fn parse_row(line: &str) -> Option<Vec<Vec<Span>>> {
+    let inner = line.trim().strip_prefix('|')?.strip_suffix('|')?;
+    Some(
+        inner
+            .split('|')
+            .map(|cell| parse_inline(cell.trim()))
+            .collect(),
+    )
+}
+
+/// Wiersz oddzielający nagłówek, np. `|---|:---:|`.
+pub(crate) fn is_separator(cells: &[Vec<Span>]) -> bool {
+    cells.iter().all(|cell| {
+        let text = plain_text(cell);
+        text.contains('-') && text.chars().all(|ch| matches!(ch, '-' | ':'))
+    })
+}
+
+/// Szerokości kolumn według najszerszej komórki; gdy tabela z ramką nie
+/// mieści się w `available` kolumnach, najszersza kolumna traci po jednej
+/// kolumnie, aż tabela się zmieści albo każda kolumna ma szerokość 1.
+pub(crate) fn column_widths(
+    headers: &[Vec<Span>],
+    rows: &[Vec<Vec<Span>>],
+    available: usize,
+) -> Vec<usize> {
+    let columns = rows.iter().map(Vec::len).chain([headers.len()]).max();
+    let mut widths = vec![MIN_COLUMN_WIDTH; columns.unwrap_or_default()];
+    for row in std::iter::once(headers).chain(rows.iter().map(Vec::as_slice)) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(spans_width(cell));
+        }
+    }
+    while table_width(&widths) > available {
+        let Some(widest) = widths
+            .iter_mut()
+            .filter(|width| **width > MIN_COLUMN_WIDTH)
+            .max_by_key(|width| **width)
+        else {
+            break;
+        };
+        *widest -= 1;
+    }
+    widths
+}
+
+fn table_width(widths: &[usize]) -> usize {
+    1 + widths
+        .iter()
+        .map(|width| width + CELL_CHROME)
+        .sum::<usize>()
+}
+
+/// Wiersze tabeli w ramce z cienkich linii: krawędzie w kolorze `dim`,
+/// nagłówek pogrubiony w kolorze `glow`, a komórki dłuższe od kolumny
+/// przycięte znakiem `›`.
+pub(crate) fn table_lines(
+    headers: &[Vec<Span>],
+    rows: &[Vec<Vec<Span>>],
+    available: usize,
+) -> Vec<Vec<Span>> {
+    let widths = column_widths(headers, rows, available);
+    let mut lines = vec![border(&widths, ['┌', '┬', '┐'])];
+    lines.push(cells_line(headers, &widths, true));
+    if !rows.is_empty() {
+        lines.push(border(&widths, ['├', '┼', '┤']));
+    }
+    lines.extend(rows.iter().map(|row| cells_line(row, &widths, false)));
+    lines.push(border(&widths, ['└', '┴', '┘']));
+    lines
+}
+
+fn edge(text: impl Into<String>) -> Span {
+    Span::styled(
+        text,
+        SpanStyle {
+            color: Some(ColorSlot::Dim),
+            ..SpanStyle::default()
+        },
+    )
+}
+
+fn border(widths: &[usize], [left, middle, right]: [char; 3]) -> Vec<Span> {
+    let cells: Vec<String> = widths
+        .iter()
+        .map(|width| "─".repeat(width + CELL_CHROME - 1))
+        .collect();
+    vec![edge(format!(
+        "{}{}{}",
+        left,
+        cells.join(&middle.to_string()),
+        right
+    ))]
+}
+
+fn cells_line(cells: &[Vec<Span>], widths: &[usize], header: bool) -> Vec<Span> {
+    let mut line = vec![edge("│")];
+    for (column, width) in widths.iter().enumerate() {
+        let fitted = fit_spans(cells.get(column).map_or(&[], Vec::as_slice), *width);
+        line.push(Span::plain(" "));
+        line.extend(fitted.spans.iter().map(|span| {
+            if header {
+                span.restyled(|style| SpanStyle {
+                    bold: true,
+                    color: Some(ColorSlot::Glow),
+                    ..style
+                })
+            } else {
+                span.clone()
+            }
+        }));
+        if fitted.truncated {
+            line.push(edge("›"));
+        }
+        line.push(Span::plain(" ".repeat(width - fitted.width + 1)));
+        line.push(edge("│"));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(line: &str) -> Vec<Vec<Span>> {
+        parse_row(line).unwrap()
+    }
+
+    #[test]
+    fn columns_fit_content_and_shrink_the_widest_first() {
+        let headers = row("| Język | Rok |");
+        let rows = vec![row("| Rust | 2015 |"), row("| Go |")];
+
+        assert_eq!(column_widths(&headers, &rows, 80), [5, 4]);
+        assert_eq!(column_widths(&headers, &rows, 14), [4, 3]);
+        assert_eq!(column_widths(&headers, &rows, 3), [1, 1]);
+        assert_eq!(column_widths(&[], &[row("| a | bb | c |")], 80), [1, 2, 1]);
+    }
+
+    #[test]
+    fn rows_need_both_pipes_and_separators_only_dashes() {
+        assert!(parse_row("a | b |").is_none());
+        assert!(parse_row("|").is_none());
+        assert!(is_separator(&row("|---|:--:|")));
+        assert!(!is_separator(&row("| - | a |")));
+        assert!(!is_separator(&row("| :: |")));
+    }
+
+    #[test]
+    fn overflowing_cells_are_truncated_within_their_column() {
+        let headers = row("| Nazwa | Opis |");
+        let rows = vec![row("| a | bardzo długi opis komórki |")];
+        let lines = table_lines(&headers, &rows, 20);
+
+        assert_eq!(lines.len(), 5);
+        for line in &lines {
+            assert_eq!(spans_width(line), 20, "{}", plain_text(line));
+        }
+        assert!(plain_text(&lines[3]).contains("›"));
+        assert!(lines[1].iter().any(|span| span.style().bold));
+    }
+}
@@ -1,6 +1,13 @@
 use serde::Deserialize;
 use std::path::Path;
 
+use crate::rules::ColorSlot;
+
+const BOOST_CEILING: f32 = 235.0;
+const BOOST_FLOOR: f32 = 28.0;
+const FOREGROUND: u8 = 38;
+const BACKGROUND: u8 = 48;
+
 #[derive(Debug, Clone, Deserialize)]
 struct RawTheme {
     #[serde(default)]
@@ -8,6 +15,30 @@ struct RawTheme {
     accent: String,
     dim: String,
     glow: String,
+    #[serde(default)]
+    background: Option<String>,
+    #[serde(default)]
+    gutter_number: Option<ColorSlot>,
+    #[serde(default)]
+    gutter_separator: Option<ColorSlot>,
+    #[serde(default)]
+    glyphs: RawGlyphs,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct RawGlyphs {
+    #[serde(default)]
+    bullet: Option<String>,
+    #[serde(default)]
+    callout_open: Option<String>,
+    #[serde(default)]
+    callout_close: Option<String>,
+    #[serde(default)]
+    heading_prefix: Option<String>,
+    #[serde(default)]
+    task_open: Option<String>,
+    #[serde(default)]
+    task_done: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +62,10 @@ pub struct ThemePalette {
     accent: String,
     dim: String,
     glow: String,
+    background: Option<String>,
+    gutter_number: ColorSlot,
+    gutter_separator: ColorSlot,
+    glyphs: ThemeGlyphs,
 }
 
 impl ThemePalette {
@@ -39,9 +74,29 @@ impl ThemePalette {
             accent: accent.into(),
             dim: dim.into(),
             glow: glow.into(),
+            background: None,
+            gutter_number: ColorSlot::Dim,
+            gutter_separator: ColorSlot::Dim,
+            glyphs: ThemeGlyphs::default(),
         }
     }
 
+    pub fn with_gutter(mut self, number: ColorSlot, separator: ColorSlot) -> Self {
+        self.gutter_number = number;
+        self.gutter_separator = separator;
+        self
+    }
+
+    pub fn with_background(mut self, background: Option<String>) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn with_glyphs(mut self, glyphs: ThemeGlyphs) -> Self {
+        self.glyphs = glyphs;
+        self
+    }
+
     pub fn accent(&self) -> &str {
         &self.accent
     }
@@ -53,6 +108,84 @@ impl ThemePalette {
     pub fn glow(&self) -> &str {
         &self.glow
     }
+
+    pub fn background(&self) -> Option<&str> {
+        self.background.as_deref()
+    }
+
+    pub fn gutter_number(&self) -> ColorSlot {
+        self.gutter_number
+    }
+
+    pub fn gutter_separator(&self) -> ColorSlot {
+        self.gutter_separator
+    }
+
+    pub fn glyphs(&self) -> &ThemeGlyphs {
+        &self.glyphs
+    }
+
+    pub fn with_contrast(mut self, factor: f32) -> Self {
+        self.accent = boost_escape(&self.accent, factor, true);
+        self.glow = boost_escape(&self.glow, factor, true);
+        self.dim = boost_escape(&self.dim, factor, false);
+        self
+    }
+
+    pub fn dimmed(mut self) -> Self {
+        self.accent = self.dim.clone();
+        self.glow = self.dim.clone();
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ThemeGlyphs {
+    bullet: String,
+    callout_open: String,
+    callout_close: String,
+    heading_prefix: String,
+    task_open: String,
+    task_done: String,
+}
+
+impl Default for ThemeGlyphs {
+    fn default() -> Self {
+        Self {
+            bullet: "•".to_string(),
+            callout_open: "❝".to_string(),
+            callout_close: "❞".to_string(),
+            heading_prefix: String::new(),
+            task_open: "☐".to_string(),
+            task_done: "☑".to_string(),
+        }
+    }
+}
+
+impl ThemeGlyphs {
+    pub fn bullet(&self) -> &str {
+        &self.bullet
+    }
+
+    pub fn callout_open(&self) -> &str {
+        &self.callout_open
+    }
+
+    pub fn callout_close(&self) -> &str {
+        &self.callout_close
+    }
+
+    pub fn heading_prefix(&self) -> &str {
+        &self.heading_prefix
+    }
+
+    pub fn task_open(&self) -> &str {
+        &self.task_open
+    }
+
+    pub fn task_done(&self) -> &str {
+        &self.task_done
+    }
 }
 
 pub fn load_from_path(path: &Path) -> Result<ThemeSpec, Box<dyn std::error::Error>> {
@@ -68,8 +201,182 @@ pub fn load_from_path(path: &Path) -> Result<ThemeSpec, Box<dyn std::error::Erro
         })
         .ok_or_else(|| format!("Plik motywu ({}) nie zawiera nazwy motywu", path.display()))?;
 
+    let defaults = ThemeGlyphs::default();
+    let glyphs = ThemeGlyphs {
+        bullet: raw.glyphs.bullet.unwrap_or(defaults.bullet),
+        callout_open: raw.glyphs.callout_open.unwrap_or(defaults.callout_open),
+        callout_close: raw.glyphs.callout_close.unwrap_or(defaults.callout_close),
+        heading_prefix: raw.glyphs.heading_prefix.unwrap_or(defaults.heading_prefix),
+        task_open: raw.glyphs.task_open.unwrap_or(defaults.task_open),
+        task_done: raw.glyphs.task_done.unwrap_or(defaults.task_done),
+    };
+
     Ok(ThemeSpec {
         label,
-        palette: ThemePalette::new(raw.accent, raw.dim, raw.glow),
+        palette: ThemePalette::new(
+            theme_color(path, "accent", raw.accent, FOREGROUND)?,
+            theme_color(path, "dim", raw.dim, FOREGROUND)?,
+            theme_color(path, "glow", raw.glow, FOREGROUND)?,
+        )
+        .with_background(
+            raw.background
+                .map(|value| theme_color(path, "background", value, BACKGROUND))
+                .transpose()?,
+        )
+        .with_gutter(
+            raw.gutter_number.unwrap_or(ColorSlot::Dim),
+            raw.gutter_separator.unwrap_or(ColorSlot::Dim),
+        )
+        .with_glyphs(glyphs),
     })
 }
+
+/// Kolor z pliku motywu: surowa sekwencja ANSI albo `#RRGGBB`, zamieniany
+/// na sekwencję truecolor tekstu (`38`) lub tła (`48`).
+fn theme_color(
+    path: &Path,
+    field: &str,
+    value: String,
+    layer: u8,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let Some(hex) = value.strip_prefix('#') else {
+        return Ok(value);
+    };
+    if hex.len() != 6 || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err(format!(
+            "Pole {} w pliku motywu ({}) ma niepoprawny kolor „{}” (oczekiwano #RRGGBB)",
+            field,
+            path.display(),
+            value
+        )
+        .into());
+    }
+    let channel = |at: usize| u8::from_str_radix(&hex[at..at + 2], 16).unwrap_or_default();
+    Ok(format!(
+        "\x1b[{};2;{};{};{}m",
+        layer,
+        channel(0),
+        channel(2),
+        channel(4)
+    ))
+}
+
+pub(crate) fn escape_rgb(escape: &str) -> Option<(u8, u8, u8)> {
+    match escape_codes(escape)?.as_slice() {
+        [38, 2, red, green, blue] => Some((*red, *green, *blue)),
+        [38, 5, index] => Some(xterm_rgb(*index)),
+        [.., code @ 30..=37] => Some(xterm_rgb(code - 30)),
+        [.., code @ 90..=97] => Some(xterm_rgb(code - 90 + 8)),
+        _ => None,
+    }
+}
+
+fn boost_escape(escape: &str, factor: f32, brighten: bool) -> String {
+    let Some(codes) = escape_codes(escape) else {
+        return escape.to_string();
+    };
+    let adjust = |(red, green, blue): (u8, u8, u8)| {
+        let channel = |value: u8| {
+            let value = f32::from(value);
+            let adjusted = if brighten {
+                (value * factor).min(BOOST_CEILING).max(value)
+            } else {
+                (value / factor).max(BOOST_FLOOR).min(value)
+            };
+            adjusted.round() as u8
+        };
+        (channel(red), channel(green), channel(blue))
+    };
+
+    match codes.as_slice() {
+        [38, 2, red, green, blue] => {
+            let (red, green, blue) = adjust((*red, *green, *blue));
+            format!("\x1b[38;2;{};{};{}m", red, green, blue)
+        }
+        [38, 5, index] => format!("\x1b[38;5;{}m", nearest_xterm(adjust(xterm_rgb(*index)))),
+        [prefix @ .., code @ 30..=37] if brighten => basic_escape(prefix, code + 60),
+        [prefix @ .., code @ 90..=97] if !brighten => basic_escape(prefix, code - 60),
+        _ => escape.to_string(),
+    }
+}
+
+fn basic_escape(prefix: &[u8], code: u8) -> String {
+    let codes: Vec<String> = prefix
+        .iter()
+        .chain(std::iter::once(&code))
+        .map(u8::to_string)
+        .collect();
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+fn nearest_xterm((red, green, blue): (u8, u8, u8)) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let level = |value: u8| {
+        (0..LEVELS.len())
+            .min_by_key(|&step| LEVELS[step].abs_diff(value))
+            .unwrap_or(0) as u8
+    };
+    let cube = 16 + 36 * level(red) + 6 * level(green) + level(blue);
+    let average = (u16::from(red) + u16::from(green) + u16::from(blue)) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 232 + gray_step;
+
+    let distance = |index: u8| {
+        let (r, g, b) = xterm_rgb(index);
+        u32::from(r.abs_diff(red)).pow(2)
+            + u32::from(g.abs_diff(green)).pow(2)
+            + u32::from(b.abs_diff(blue)).pow(2)
+    };
+    if distance(gray) < distance(cube) {
+        gray
+    } else {
+        cube
+    }
+}
+
+fn escape_codes(escape: &str) -> Option<Vec<u8>> {
+    escape
+        .strip_prefix("\x1b[")?
+        .strip_suffix('m')?
+        .split(';')
+        .map(|code| code.parse().ok())
+        .collect()
+}
+
+fn xterm_rgb(index: u8) -> (u8, u8, u8) {
+    const BASIC: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (128, 0, 0),
+        (0, 128, 0),
+        (128, 128, 0),
+        (0, 0, 128),
+        (128, 0, 128),
+        (0, 128, 128),
+        (192, 192, 192),
+        (128, 128, 128),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (0, 0, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    match index {
+        0..=15 => BASIC[usize::from(index)],
+        16..=231 => {
+            let cube = index - 16;
+            (
+                LEVELS[usize::from(cube / 36)],
+                LEVELS[usize::from(cube / 6 % 6)],
+                LEVELS[usize::from(cube % 6)],
+            )
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::sources;
+
+pub(crate) struct FileWatcher {
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    targets: Vec<PathBuf>,
+    directories: Vec<PathBuf>,
+    trace: bool,
+}
+
+impl FileWatcher {
+    /// Katalog na liście obserwuje dodawane, usuwane i zmieniane pliki
+    /// `.txt`/`.md`; pozostałe ścieżki to pojedyncze pliki.
+    pub(crate) fn new(paths: &[&Path], trace: bool) -> notify::Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = sender.send(event);
+        })?;
+
+        let mut targets = Vec::with_capacity(paths.len());
+        let mut directories = Vec::new();
+        for path in paths {
+            let target = absolute(path);
+            if target.is_dir() {
+                watcher.watch(&target, RecursiveMode::NonRecursive)?;
+                if trace {
+                    eprintln!("[watch] obserwacja katalogu {}", target.display());
+                }
+                directories.push(target);
+                continue;
+            }
+            let directory = target
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| PathBuf::from("."));
+            watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+            if trace {
+                eprintln!(
+                    "[watch] obserwacja {} (katalog {})",
+                    target.display(),
+                    directory.display()
+                );
+            }
+            targets.push(target);
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            targets,
+            directories,
+            trace,
+        })
+    }
+
+    pub(crate) fn changed(&self) -> bool {
+        let mut relevant = 0;
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                Ok(event) => {
+                    let is_relevant = is_relevant_event(&event, &self.targets, &self.directories);
+                    if self.trace {
+                        eprintln!(
+                            "[watch] {:?} {:?} istotne={}",
+                            event.kind, event.paths, is_relevant
+                        );
+                    }
+                    relevant += usize::from(is_relevant);
+                }
+                Err(error) if self.trace => eprintln!("[watch] błąd: {}", error),
+                Err(_) => {}
+            }
+        }
+        if self.trace && relevant > 0 {
+            eprintln!(
+                "[watch] {} istotnych zdarzeń → jedno przeładowanie",
+                relevant
+            );
+        }
+        relevant > 0
+    }
+}
+
+fn is_relevant_event(event: &Event, targets: &[PathBuf], directories: &[PathBuf]) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) && event.paths.iter().any(|path| {
+        targets.contains(path)
+            || path
+                .parent()
+                .is_some_and(|parent| directories.iter().any(|directory| directory == parent))
+                && sources::is_eligible(path)
+    })
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| {
+        std::env::current_dir()
+            .map(|current| current.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    fn settles(watcher: &FileWatcher) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(3);
+        while Instant::now() < deadline {
+            if watcher.changed() {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        false
+    }
+
+    #[test]
+    fn directory_targets_react_to_added_and_removed_slide_files() {
+        let directory =
+            std::env::temp_dir().join(format!("presentation-watch-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("01-wstep.md"), "wstęp\n").unwrap();
+        let watcher = FileWatcher::new(&[directory.as_path()], false).unwrap();
+
+        fs::write(directory.join("notatka.png"), "").unwrap();
+        thread::sleep(Duration::from_millis(300));
+        assert!(!watcher.changed());
+
+        fs::write(directory.join("02-dalej.md"), "dalej\n").unwrap();
+        assert!(settles(&watcher));
+        fs::remove_file(directory.join("01-wstep.md")).unwrap();
+        assert!(settles(&watcher));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,19 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+#[test]
+fn reports_invalid_rule_pattern() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--instant")
+        .arg("--skip-banner")
+        .arg("--rules")
+        .arg("tests/fixtures/invalid_rules.toml")
+        .arg("tests/fixtures/empty.txt");
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Reguła unclosed w pliku tests/fixtures/invalid_rules.toml ma nieprawidłowy wzorzec",
+    ));
+
+    Ok(())
+}
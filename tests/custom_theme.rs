@@ -17,3 +17,38 @@ fn runs_with_custom_theme_path() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn runs_with_hex_theme_colors() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--instant")
+        .arg("--skip-banner")
+        .arg("--theme-path")
+        .arg("themes/hexy.toml")
+        .arg("tests/fixtures/empty.txt");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("HEXY"))
+        .stdout(predicate::str::contains("\x1b[38;2;97;218;251m"));
+
+    Ok(())
+}
+
+#[test]
+fn reports_invalid_hex_color_with_field_and_file() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--instant")
+        .arg("--skip-banner")
+        .arg("--theme-path")
+        .arg("tests/fixtures/invalid_hex_theme.toml")
+        .arg("tests/fixtures/empty.txt");
+
+    cmd.assert().failure().stderr(
+        predicate::str::contains("Pole dim")
+            .and(predicate::str::contains("invalid_hex_theme.toml"))
+            .and(predicate::str::contains("#5c63")),
+    );
+
+    Ok(())
+}
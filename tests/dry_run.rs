@@ -0,0 +1,246 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+#[test]
+fn dry_run_reports_the_parsed_deck() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--dry-run")
+        .arg("--skip-banner")
+        .arg("--theme")
+        .arg("amber")
+        .arg("--frame-width")
+        .arg("80")
+        .arg("tests/fixtures/three_lines.txt");
+
+    cmd.assert().success().stdout(predicate::str::contains(
+        "OK: 3 sekwencji, motyw AMBER, ramka 80",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_fails_on_missing_banner() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--dry-run")
+        .arg("--banner")
+        .arg("tests/fixtures/missing_banner.txt")
+        .arg("tests/fixtures/three_lines.txt");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("missing_banner.txt"));
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_joins_multiple_scripts() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--dry-run")
+        .arg("--skip-banner")
+        .arg("--join")
+        .arg("separator")
+        .arg("tests/fixtures/three_lines.txt")
+        .arg("tests/fixtures/empty.txt")
+        .arg("tests/fixtures/three_lines.txt");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("OK: 7 sekwencji"));
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_collects_a_directory() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--dry-run")
+        .arg("--skip-banner")
+        .arg("--sort")
+        .arg("numeric")
+        .arg("tests/fixtures/deck");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("OK: 3 sekwencji"));
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_fails_on_missing_end_slide() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--dry-run")
+        .arg("--skip-banner")
+        .arg("--end-slide=tests/fixtures/missing_end.txt")
+        .arg("tests/fixtures/three_lines.txt");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("missing_end.txt"));
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_reflows_wrapped_paragraphs() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--dry-run")
+        .arg("--skip-banner")
+        .arg("--reflow")
+        .arg("tests/fixtures/wrapped.txt");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("OK: 4 sekwencji"));
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_keeps_spacer_between_paragraphs() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--dry-run")
+        .arg("--skip-banner")
+        .arg("--reflow")
+        .arg("tests/fixtures/spaced.txt");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("OK: 3 sekwencji"));
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_rejects_invalid_replay_log() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--dry-run")
+        .arg("--skip-banner")
+        .arg("--replay")
+        .arg("tests/fixtures/invalid_replay.log")
+        .arg("tests/fixtures/three_lines.txt");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("linii 2"));
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_rejects_pause_beyond_deck() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--dry-run")
+        .arg("--skip-banner")
+        .arg("--replay")
+        .arg("tests/fixtures/replay.log")
+        .arg("--pause-at")
+        .arg("2,4")
+        .arg("tests/fixtures/three_lines.txt");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Sekwencja 4 z --pause-at"));
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_warns_about_unknown_slide_theme() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--dry-run")
+        .arg("--skip-banner")
+        .arg("tests/fixtures/slide_theme.txt");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("OK: 3 sekwencji"))
+        .stderr(predicate::str::contains(
+            "sekwencja 3: Nieznany motyw sekwencji „nieistniejacy”",
+        ))
+        .stderr(predicate::str::contains("amber").not());
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_splits_headings_into_slides() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--dry-run")
+        .arg("--skip-banner")
+        .arg("--split-on-heading")
+        .arg("tests/fixtures/headings.txt");
+
+    // `## Szczegóły planu` zostaje na sekwencji `# Plan`.
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("OK: 3 sekwencji"));
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_keeps_preamble_as_first_slide() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--dry-run")
+        .arg("--skip-banner")
+        .arg("--split-on-heading")
+        .arg("tests/fixtures/outline.txt");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("OK: 3 sekwencji"));
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_counts_contents_slide() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--dry-run")
+        .arg("--skip-banner")
+        .arg("--split-on-heading")
+        .arg("--toc")
+        .arg("tests/fixtures/outline.txt");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("OK: 4 sekwencji"));
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_rejects_unknown_footer_field() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--dry-run")
+        .arg("--skip-banner")
+        .arg("--footer-fields")
+        .arg("seq,next")
+        .arg("tests/fixtures/three_lines.txt");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("next"));
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_rejects_out_of_range_contrast_boost() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--dry-run")
+        .arg("--skip-banner")
+        .arg("--theme-contrast-boost")
+        .arg("5")
+        .arg("tests/fixtures/three_lines.txt");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("kontrastu"));
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+#[test]
+fn export_pdf_reports_missing_renderer() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.env("PATH", "")
+        .arg("--skip-banner")
+        .arg("--export-pdf")
+        .arg("target/export.pdf")
+        .arg("tests/fixtures/three_lines.txt");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("wkhtmltopdf"));
+
+    Ok(())
+}
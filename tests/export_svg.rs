@@ -0,0 +1,34 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn export_svg_writes_one_escaped_file_per_slide() -> Result<(), Box<dyn std::error::Error>> {
+    let directory = Path::new(env!("CARGO_TARGET_TMPDIR")).join("export-svg");
+    let _ = fs::remove_dir_all(&directory);
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--skip-banner")
+        .arg("--frame-width")
+        .arg("60")
+        .arg("--export-svg")
+        .arg(&directory)
+        .arg("tests/fixtures/wide_text.txt");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("(2 plików)"));
+
+    let heading = fs::read_to_string(directory.join("slide-001.svg"))?;
+    assert!(heading.starts_with("<svg "));
+    assert!(heading.contains(">Porównanie</text>"));
+    assert!(heading.contains("RUST &amp; &lt;C++&gt;"));
+
+    let bullet = fs::read_to_string(directory.join("slide-002.svg"))?;
+    assert!(bullet.contains("<tspan x=\"62.4\">日</tspan><tspan x=\"81.6\">本</tspan>"));
+    assert!(bullet.contains("<tspan x=\"129.6\">🚀</tspan><tspan x=\"148.8\"> test</tspan>"));
+    assert!(!directory.join("slide-003.svg").exists());
+
+    Ok(())
+}
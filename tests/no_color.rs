@@ -0,0 +1,61 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+use assert_cmd::cargo::cargo_bin;
+
+#[test]
+fn no_color_flag_strips_ansi_escapes() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--no-color")
+        .arg("--instant")
+        .arg("--skip-banner")
+        .arg("tests/fixtures/empty.txt");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("SYS :: (brak treści w pliku)"))
+        .stdout(predicate::str::contains("\x1b[").not());
+
+    Ok(())
+}
+
+#[test]
+fn no_color_env_covers_the_banner() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.env("NO_COLOR", "1")
+        .arg("--instant")
+        .arg("--banner")
+        .arg("tests/fixtures/banner.txt")
+        .arg("tests/fixtures/empty.txt");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("RUST"))
+        .stdout(predicate::str::contains("\x1b[").not());
+
+    Ok(())
+}
+
+/// Prezentacja z treścią wymaga terminala, więc działa w pseudoterminalu
+/// `script` z util-linux; bez niego test jest pomijany.
+#[test]
+fn no_color_flag_drops_hyperlinks_and_title() -> Result<(), Box<dyn std::error::Error>> {
+    if Command::new("script").arg("--version").output().is_err() {
+        return Ok(());
+    }
+    let run = format!(
+        "{} --no-color --instant --skip-banner tests/fixtures/links.txt",
+        cargo_bin(env!("CARGO_PKG_NAME")).display()
+    );
+    let mut cmd = assert_cmd::Command::new("script");
+    cmd.args(["-qec", &run, "/dev/null"]).write_stdin("q");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("https://rust-lang.org"))
+        .stdout(predicate::str::contains("dokumentacja"))
+        .stdout(predicate::str::contains("\x1b]").not());
+
+    Ok(())
+}